@@ -0,0 +1,152 @@
+use crate::{fetch_banks, i18n, rate_type_name, Banks, Colorized, CurrencyType, ERR_MSG};
+use anyhow::{Context, Result};
+use armbankrate_parser::best::{best_rates, best_rates_json, BestRates};
+use armbankrate_parser::validation::Side;
+use armbankrate_parser::CurrencyName;
+use clap::ArgEnum;
+use colored::Colorize;
+use std::fmt::Display;
+use tabled::builder::Builder;
+use tabled::object::Segment;
+use tabled::{Alignment, Header, Modify, Style};
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum BestFormat {
+    Table,
+    Json,
+    Line,
+}
+
+pub(crate) async fn handle_best(
+    banks: Vec<Banks>,
+    currency_type: CurrencyType,
+    currencies: Vec<CurrencyName>,
+    top: usize,
+    format: BestFormat,
+) -> Result<()> {
+    let banks = fetch_banks(&banks).await?;
+
+    let best_rates: Vec<BestRates> = best_rates(&banks, top)
+        .into_iter()
+        .filter(|best| matches_type(best, &currency_type))
+        .filter(|best| currencies.is_empty() || currencies.contains(best.currency_name()))
+        .collect();
+
+    match format {
+        BestFormat::Table => println!("{}", build_table(&best_rates)),
+        BestFormat::Line => println!("{}", build_line(&best_rates, &currency_type)),
+        BestFormat::Json => {
+            let json = best_rates_json(&best_rates).with_context(|| ERR_MSG)?;
+            println!("{json}");
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_type(best: &BestRates, currency_type: &CurrencyType) -> bool {
+    currency_type.rate_types().contains(best.currency_type())
+}
+
+fn format_margin(margin: Option<f64>) -> String {
+    match margin {
+        Some(margin) => i18n::format_signed(margin, 2),
+        None => "-".to_string(),
+    }
+}
+
+fn build_table(best_rates: &[BestRates]) -> String {
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns([
+            "type",
+            "currency",
+            "side",
+            "rank",
+            "rate",
+            "banks",
+            "vs_second",
+            "vs_median",
+        ])
+        .map(|column| column.as_str().colorized()),
+    );
+
+    for best in best_rates {
+        let side = match best.side() {
+            Side::Buy => i18n::tr("side.buy").bright_green().to_string(),
+            Side::Sell => i18n::tr("side.sell").bright_red().to_string(),
+        };
+
+        for (index, entry) in best.entries().iter().enumerate() {
+            let (margin_to_second, margin_to_median) = match index {
+                0 => (
+                    format_margin(best.margin_to_second()),
+                    format_margin(best.margin_to_median()),
+                ),
+                _ => (String::new(), String::new()),
+            };
+
+            builder.add_record([
+                rate_type_name(best.currency_type()),
+                i18n::currency_name(best.currency_name()),
+                side.clone(),
+                entry.rank().to_string(),
+                i18n::format_number(entry.rate()),
+                bank_names(entry.banks(), ", ")
+                    .bright_yellow()
+                    .bold()
+                    .to_string(),
+                margin_to_second,
+                margin_to_median,
+            ]);
+        }
+    }
+
+    builder
+        .build()
+        .with(Header(
+            i18n::tr("title.best").bold().bright_green().to_string(),
+        ))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended())
+        .to_string()
+}
+
+/// Compact single line summary, e.g. for a status bar.
+fn build_line(best_rates: &[BestRates], currency_type: &CurrencyType) -> String {
+    let mut parts = Vec::new();
+
+    // Buy and sell rates of a currency come in pairs
+    for pair in best_rates.chunks(2) {
+        let (buy, sell) = match pair {
+            [buy, sell] => (buy, sell),
+            _ => continue,
+        };
+        let side = |best: &BestRates| match best.best() {
+            Some(entry) => format!(
+                "{} {}",
+                i18n::format_number(entry.rate()),
+                bank_names(entry.banks(), "/")
+            ),
+            None => "-".to_string(),
+        };
+
+        let prefix = match currency_type {
+            CurrencyType::All => format!("{} ", rate_type_name(buy.currency_type())),
+            _ => String::new(),
+        };
+        parts.push(format!(
+            "{prefix}{:?} ↑{} ↓{}",
+            buy.currency_name(),
+            side(buy),
+            side(sell)
+        ));
+    }
+
+    parts.join(" | ")
+}
+
+fn bank_names(banks: &[String], separator: &str) -> String {
+    let names: Vec<String> = banks.iter().map(|bank| i18n::bank_name(bank)).collect();
+    names.join(separator)
+}
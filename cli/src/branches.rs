@@ -0,0 +1,159 @@
+use crate::{i18n, Banks, Colorized, ERR_MSG, MISSING};
+use anyhow::{Context, Result};
+use armbankrate_parser::branches::{
+    best_branch_rates, best_branch_rates_json, branches_json, BranchRate,
+};
+use armbankrate_parser::validation::Side;
+use armbankrate_parser::{Bank, BankImpl, CurrencyName};
+use clap::ArgEnum;
+use colored::Colorize;
+use std::fmt::Display;
+use tabled::builder::Builder;
+use tabled::object::Segment;
+use tabled::{Alignment, Header, Modify, Style};
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum BranchesFormat {
+    Table,
+    Json,
+}
+
+/// Lists the branches of the banks, or the best cash rates of `best` with the branches they apply at.
+pub(crate) async fn handle_branches(
+    banks: Vec<Banks>,
+    best: Option<CurrencyName>,
+    top: usize,
+    format: BranchesFormat,
+) -> Result<()> {
+    let banks = match banks.is_empty() || banks.contains(&Banks::All) {
+        true => armbankrate_parser::branches::parse_all().await,
+        false => armbankrate_parser::branches::parse(&banks).await,
+    }
+    .with_context(|| ERR_MSG)?;
+
+    let currency_name = match best {
+        Some(currency_name) => currency_name,
+        None => {
+            match format {
+                BranchesFormat::Json => {
+                    println!("{}", branches_json(&banks).with_context(|| ERR_MSG)?)
+                }
+                BranchesFormat::Table => match build_branches(&banks) {
+                    Some(table) => println!("{table}"),
+                    None => eprintln!("{}", i18n::tr("message.no_branches")),
+                },
+            }
+            return Ok(());
+        }
+    };
+
+    let buy = best_branch_rates(&banks, &currency_name, Side::Buy, top);
+    let sell = best_branch_rates(&banks, &currency_name, Side::Sell, top);
+    match format {
+        BranchesFormat::Json => {
+            let rates: Vec<BranchRate> = buy.into_iter().chain(sell).collect();
+            println!(
+                "{}",
+                best_branch_rates_json(&rates).with_context(|| ERR_MSG)?
+            );
+        }
+        BranchesFormat::Table => println!("{}", build_best(&currency_name, &buy, &sell)),
+    }
+
+    Ok(())
+}
+
+fn build_branches(banks: &[Bank]) -> Option<String> {
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns([
+            "bank",
+            "branch",
+            "address",
+            "hours",
+            "coordinates",
+            "cash_rates",
+        ])
+        .map(|column| column.as_str().colorized()),
+    );
+
+    let mut rows = 0;
+    for bank in banks {
+        for branch in bank.branches() {
+            let coordinates = match branch.coordinates() {
+                Some(coordinates) => format!(
+                    "{:.4}, {:.4}",
+                    coordinates.latitude(),
+                    coordinates.longitude()
+                ),
+                None => MISSING.to_string(),
+            };
+            let cash_rates = match branch.cash_currencies() {
+                Some(_) => i18n::tr("branches.own").bright_green().to_string(),
+                None => i18n::tr("branches.bank_wide"),
+            };
+
+            builder.add_record([
+                i18n::bank_name(bank.get_name())
+                    .bright_yellow()
+                    .bold()
+                    .to_string(),
+                branch.name().to_string(),
+                branch.address().to_string(),
+                branch.hours().unwrap_or(MISSING).to_string(),
+                coordinates,
+                cash_rates,
+            ]);
+            rows += 1;
+        }
+    }
+
+    if rows == 0 {
+        return None;
+    }
+
+    let table = builder
+        .build()
+        .with(Header(
+            i18n::tr("title.branches").bold().bright_green().to_string(),
+        ))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended());
+    Some(table.to_string())
+}
+
+fn build_best(currency_name: &CurrencyName, buy: &[BranchRate], sell: &[BranchRate]) -> String {
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns(["side", "rank", "rate", "bank", "branch", "address"])
+            .map(|column| column.as_str().colorized()),
+    );
+
+    for (side, rates) in [
+        (i18n::tr("side.buy").bright_green().to_string(), buy),
+        (i18n::tr("side.sell").bright_red().to_string(), sell),
+    ] {
+        for (index, rate) in rates.iter().enumerate() {
+            builder.add_record([
+                side.clone(),
+                (index + 1).to_string(),
+                i18n::format_number(rate.rate()),
+                i18n::bank_name(rate.bank())
+                    .bright_yellow()
+                    .bold()
+                    .to_string(),
+                rate.branch()
+                    .map_or_else(|| i18n::tr("branches.bank_wide"), str::to_string),
+                rate.address().unwrap_or(MISSING).to_string(),
+            ]);
+        }
+    }
+
+    let title = i18n::tr_with("title.best_branches", i18n::currency_name(currency_name));
+    builder
+        .build()
+        .with(Header(title.bold().bright_green().to_string()))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended())
+        .to_string()
+}
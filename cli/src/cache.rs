@@ -0,0 +1,159 @@
+use crate::{i18n, Banks, Colorized};
+use anyhow::{Context, Result};
+use armbankrate_parser::cache::{self, CacheMode};
+use clap::{ArgEnum, Args, Subcommand};
+use colored::Colorize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tabled::builder::Builder;
+use tabled::object::Segment;
+use tabled::{Alignment, Header, Modify, Style};
+
+#[derive(Args, Debug)]
+pub(crate) struct CacheArgs {
+    /// Always download the rates, neither reading nor writing the response cache
+    #[clap(long, global = true, action, conflicts_with = "refresh")]
+    no_cache: bool,
+
+    /// Revalidate cached responses with the banks even when they are still fresh
+    #[clap(long, global = true, action)]
+    refresh: bool,
+
+    /// How long responses stay fresh, for all banks or one (e.g. 10m, idbank=1h), can be repeated
+    #[clap(long, global = true, value_parser)]
+    cache_ttl: Vec<CacheTtl>,
+
+    /// Directory of the response cache
+    #[clap(long, global = true, value_parser)]
+    cache_dir: Option<PathBuf>,
+}
+
+impl CacheArgs {
+    /// Configures the parser cache, `polling` commands revalidate fresh entries as well.
+    pub(crate) fn apply(&self, polling: bool) {
+        let mode = match (self.no_cache, self.refresh || polling) {
+            (true, _) => CacheMode::Disabled,
+            (false, true) => CacheMode::Refresh,
+            (false, false) => CacheMode::Enabled,
+        };
+        cache::set_mode(mode);
+
+        if let Some(dir) = &self.cache_dir {
+            cache::set_dir(dir.clone());
+        }
+        for cache_ttl in &self.cache_ttl {
+            match &cache_ttl.bank {
+                Some(bank) => cache::set_ttl(bank, cache_ttl.ttl),
+                None => cache::set_default_ttl(cache_ttl.ttl),
+            }
+        }
+    }
+}
+
+/// Time-to-live in the form `[<bank>=]<duration>`.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheTtl {
+    bank: Option<String>,
+    ttl: Duration,
+}
+
+impl FromStr for CacheTtl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (bank, ttl) = match value.split_once('=') {
+            Some((bank, ttl)) => (Some(bank), ttl),
+            None => (None, value),
+        };
+
+        let bank = match bank.map(|bank| Banks::from_str(bank, true)).transpose()? {
+            Some(Banks::All) | None => None,
+            Some(bank) => Some(bank.to_string()),
+        };
+        let ttl = humantime::parse_duration(ttl)
+            .map_err(|error| format!("invalid cache ttl `{value}`: {error}"))?;
+
+        Ok(Self { bank, ttl })
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum CacheCommand {
+    /// Lists cached responses with their age and validators
+    List,
+    /// Removes cached responses of all banks or the selected one
+    Clear {
+        #[clap(value_enum)]
+        bank: Option<Banks>,
+    },
+    /// Prints the cache directory
+    Path,
+}
+
+pub(crate) fn handle_cache(command: CacheCommand) -> Result<()> {
+    match command {
+        CacheCommand::List => println!("{}", build_table()?),
+        CacheCommand::Clear { bank } => {
+            let bank = bank
+                .filter(|bank| *bank != Banks::All)
+                .map(|bank| bank.to_string());
+            let removed =
+                cache::clear(bank.as_deref()).with_context(|| "Failed to clear the cache")?;
+            println!("{}", i18n::tr_with("cache.removed", removed));
+        }
+        CacheCommand::Path => println!("{}", cache::dir().display()),
+    }
+
+    Ok(())
+}
+
+fn build_table() -> Result<String> {
+    let entries = cache::entries().with_context(|| "Failed to read the cache")?;
+    if entries.is_empty() {
+        return Ok(i18n::tr_with("cache.empty", cache::dir().display()));
+    }
+
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns([
+            "bank",
+            "url",
+            "age",
+            "ttl",
+            "status",
+            "etag",
+            "last_modified",
+        ])
+        .map(|column| column.as_str().colorized()),
+    );
+
+    for entry in entries {
+        let status = match entry.is_fresh() {
+            true => i18n::tr("cache.fresh").bright_green().to_string(),
+            false => i18n::tr("cache.expired").bright_red().to_string(),
+        };
+
+        builder.add_record([
+            i18n::bank_name(entry.bank())
+                .bright_yellow()
+                .bold()
+                .to_string(),
+            entry.url().to_string(),
+            humantime::format_duration(Duration::from_secs(entry.age().as_secs())).to_string(),
+            humantime::format_duration(entry.ttl()).to_string(),
+            status,
+            entry.etag().unwrap_or("-").to_string(),
+            entry.last_modified().unwrap_or("-").to_string(),
+        ]);
+    }
+
+    Ok(builder
+        .build()
+        .with(Header(
+            i18n::tr("title.cache").bold().bright_green().to_string(),
+        ))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended())
+        .to_string())
+}
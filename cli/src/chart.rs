@@ -0,0 +1,256 @@
+use crate::i18n::{self, tr};
+use crate::{Banks, Colorized, CurrencyType};
+use anyhow::{Context, Result};
+use armbankrate_parser::history::History;
+use armbankrate_parser::validation::Side;
+use armbankrate_parser::CurrencyName;
+use chrono::{DateTime, Local, Utc};
+use clap::ArgEnum;
+use colored::Colorize;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Widest line chart, older points are dropped beyond it.
+const MAX_WIDTH: usize = 80;
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum ChartStyle {
+    Sparkline,
+    Line,
+}
+
+type Series = Vec<(DateTime<Utc>, f64)>;
+
+pub(crate) fn handle_chart(
+    dir: PathBuf,
+    banks: Vec<Banks>,
+    currency_type: CurrencyType,
+    currencies: Vec<CurrencyName>,
+    since: Option<Duration>,
+    style: ChartStyle,
+    height: usize,
+) -> Result<()> {
+    let history = History::load_dir(&dir)
+        .with_context(|| format!("Failed to read snapshots from {}", dir.display()))?;
+
+    let since = match since {
+        Some(since) => Some(
+            chrono::Duration::from_std(since)
+                .ok()
+                .and_then(|since| Utc::now().checked_sub_signed(since))
+                .with_context(|| {
+                    format!(
+                        "--since {} reaches too far back",
+                        humantime::format_duration(since)
+                    )
+                })?,
+        ),
+        None => None,
+    };
+    let currencies = match currencies.is_empty() {
+        true => vec![CurrencyName::USD],
+        false => currencies,
+    };
+    let rate_types: Vec<&str> = currency_type
+        .rate_types()
+        .iter()
+        .map(|rate_type| rate_type.key())
+        .collect();
+    let bank_names: Vec<&str> = history
+        .banks()
+        .into_iter()
+        .filter(|name| {
+            banks.is_empty()
+                || banks.contains(&Banks::All)
+                || banks
+                    .iter()
+                    .any(|bank| bank.to_string().eq_ignore_ascii_case(name))
+        })
+        .collect();
+
+    for rate_type in &rate_types {
+        for currency_name in &currencies {
+            let currency = format!("{currency_name:?}");
+            println!(
+                "{}",
+                format!("{currency} {}", tr(&format!("header.{rate_type}")))
+                    .as_str()
+                    .colorized()
+            );
+
+            for bank in &bank_names {
+                let buy = history.series(bank, rate_type, &currency, Side::Buy, since);
+                let sell = history.series(bank, rate_type, &currency, Side::Sell, since);
+                if buy.is_empty() && sell.is_empty() {
+                    continue;
+                }
+
+                match style {
+                    ChartStyle::Sparkline => print_sparklines(bank, &buy, &sell),
+                    ChartStyle::Line => print_line_chart(bank, &buy, &sell, height),
+                }
+            }
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+fn bounds(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values.fold(None, |bounds, value| match bounds {
+        None => Some((value, value)),
+        Some((min, max)) => Some((min.min(value), max.max(value))),
+    })
+}
+
+/// Position of `value` between `min` and `max` scaled to `0..levels`.
+fn level(value: f64, min: f64, max: f64, levels: usize) -> usize {
+    if (max - min).abs() < f64::EPSILON {
+        return levels / 2;
+    }
+
+    (((value - min) / (max - min)) * (levels - 1) as f64).round() as usize
+}
+
+fn sparkline(series: &Series) -> String {
+    let (min, max) = match bounds(series.iter().map(|(_, value)| *value)) {
+        Some(bounds) => bounds,
+        None => return String::new(),
+    };
+
+    series
+        .iter()
+        .map(|(_, value)| SPARKS[level(*value, min, max, SPARKS.len())])
+        .collect()
+}
+
+fn print_sparklines(bank: &str, buy: &Series, sell: &Series) {
+    let range = |series: &Series| match (series.first(), series.last()) {
+        (Some((_, first)), Some((_, last))) => format!("{first} → {last}"),
+        _ => "-".to_string(),
+    };
+
+    println!(
+        "{:<14} {} {:<24} {} {}",
+        i18n::bank_name(bank).bright_yellow().bold(),
+        sparkline(buy).bright_green(),
+        range(buy),
+        sparkline(sell).bright_red(),
+        range(sell)
+    );
+}
+
+/// Buy and sell points share the columns of their snapshot times, so the lines stay aligned
+/// when a snapshot misses one side.
+fn print_line_chart(bank: &str, buy: &Series, sell: &Series, height: usize) {
+    let mut times: Vec<DateTime<Utc>> = buy.iter().chain(sell).map(|(time, _)| *time).collect();
+    times.sort();
+    times.dedup();
+    let times = &times[times.len().saturating_sub(MAX_WIDTH)..];
+
+    let in_window = |series: &Series| -> Vec<(usize, f64)> {
+        series
+            .iter()
+            .filter_map(|(time, value)| Some((times.binary_search(time).ok()?, *value)))
+            .collect()
+    };
+    let (buy, sell) = (in_window(buy), in_window(sell));
+    let (min, max) = match bounds(buy.iter().chain(&sell).map(|(_, value)| *value)) {
+        Some(bounds) => bounds,
+        None => return,
+    };
+    let height = height.max(2);
+
+    let mut grid = vec![vec![' '.to_string(); times.len()]; height];
+    for (series, point) in [
+        (&buy, "•".bright_green().to_string()),
+        (&sell, "•".bright_red().to_string()),
+    ] {
+        for (column, value) in series {
+            let row = height - 1 - level(*value, min, max, height);
+            grid[row][*column] = match grid[row][*column].as_str() {
+                " " => point.clone(),
+                _ => "◆".bright_yellow().to_string(),
+            };
+        }
+    }
+
+    println!("{}", i18n::bank_name(bank).bright_yellow().bold());
+    for (index, row) in grid.iter().enumerate() {
+        let label = match index {
+            0 => format!("{max:>10.2}"),
+            _ if index == height - 1 => format!("{min:>10.2}"),
+            _ => " ".repeat(10),
+        };
+        println!("{label} ┤{}", row.concat());
+    }
+
+    if let (Some(first), Some(last)) = (times.first(), times.last()) {
+        let format = |time: &DateTime<Utc>| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        };
+        println!(
+            "{} └ {} … {}   {} {}",
+            " ".repeat(10),
+            format(first),
+            format(last),
+            format!("• {}", tr("side.buy")).bright_green(),
+            format!("• {}", tr("side.sell")).bright_red()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn series(values: &[f64]) -> Series {
+        values
+            .iter()
+            .enumerate()
+            .map(|(hour, value)| {
+                let time = Utc
+                    .with_ymd_and_hms(2026, 10, 1, hour as u32, 0, 0)
+                    .unwrap();
+                (time, *value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bounds_are_min_and_max() {
+        assert_eq!(
+            bounds([386.0, 384.5, 389.0].into_iter()),
+            Some((384.5, 389.0))
+        );
+        assert_eq!(bounds(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn levels_scale_between_bounds() {
+        assert_eq!(level(384.0, 384.0, 392.0, 8), 0);
+        assert_eq!(level(392.0, 384.0, 392.0, 8), 7);
+        assert_eq!(level(388.0, 384.0, 392.0, 8), 4);
+        assert_eq!(level(385.0, 384.0, 392.0, 2), 0);
+        assert_eq!(level(391.0, 384.0, 392.0, 2), 1);
+    }
+
+    #[test]
+    fn flat_series_sits_in_the_middle() {
+        assert_eq!(level(386.0, 386.0, 386.0, 8), 4);
+        assert_eq!(sparkline(&series(&[386.0, 386.0, 386.0])), "▅▅▅");
+    }
+
+    #[test]
+    fn sparkline_has_a_spark_per_point() {
+        assert_eq!(sparkline(&series(&[])), "");
+        assert_eq!(sparkline(&series(&[384.0, 386.0, 392.0, 388.0])), "▁▃█▅");
+    }
+}
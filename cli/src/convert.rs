@@ -0,0 +1,98 @@
+use crate::{fetch_banks, i18n, rate_type_name, Banks, Colorized, CurrencyType, ERR_MSG, MISSING};
+use anyhow::{Context, Result};
+use armbankrate_parser::convert::{conversions_json, convert, Exchange};
+use armbankrate_parser::CurrencyName;
+use clap::ArgEnum;
+use colored::Colorize;
+use std::fmt::Display;
+use tabled::builder::Builder;
+use tabled::object::Segment;
+use tabled::{Alignment, Header, Modify, Style};
+
+#[derive(ArgEnum, Display, Debug, Clone, Copy)]
+pub(crate) enum ExchangeSide {
+    Sell,
+    Buy,
+}
+
+impl From<ExchangeSide> for Exchange {
+    fn from(side: ExchangeSide) -> Self {
+        match side {
+            ExchangeSide::Sell => Exchange::Sell,
+            ExchangeSide::Buy => Exchange::Buy,
+        }
+    }
+}
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum ConvertFormat {
+    Table,
+    Json,
+}
+
+pub(crate) async fn handle_convert(
+    amount: f64,
+    currency_name: CurrencyName,
+    banks: Vec<Banks>,
+    side: ExchangeSide,
+    currency_type: CurrencyType,
+    format: ConvertFormat,
+) -> Result<()> {
+    let banks = fetch_banks(&banks).await?;
+    let conversions = convert(
+        &banks,
+        &currency_type.rate_types(),
+        &currency_name,
+        side.into(),
+        amount,
+    );
+
+    if let ConvertFormat::Json = format {
+        let json = conversions_json(&conversions).with_context(|| ERR_MSG)?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns(["bank", "type", "rate", "tier", "amd"])
+            .map(|column| column.as_str().colorized()),
+    );
+
+    for (index, conversion) in conversions.iter().enumerate() {
+        let total = i18n::format_number((conversion.total() * 100.0).round() / 100.0);
+        let tier = match conversion.tier() {
+            Some(min_amount) => format!("≥ {}", i18n::format_number(min_amount)),
+            None => MISSING.to_string(),
+        };
+
+        builder.add_record([
+            i18n::bank_name(conversion.bank())
+                .bright_yellow()
+                .bold()
+                .to_string(),
+            rate_type_name(conversion.currency_type()),
+            i18n::format_number(conversion.rate()),
+            tier,
+            match index {
+                0 => total.bright_green().bold().to_string(),
+                _ => total,
+            },
+        ]);
+    }
+
+    let title = format!(
+        "{} {} {}",
+        i18n::tr(&format!("exchange.{}", side.to_string().to_lowercase())),
+        i18n::format_number(amount),
+        i18n::currency_name(&currency_name)
+    );
+    let table = builder
+        .build()
+        .with(Header(title.bold().bright_green().to_string()))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended());
+    println!("{table}");
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use crate::{fetch_banks, i18n, Colorized, ERR_MSG, MISSING};
+use anyhow::{Context, Result};
+use armbankrate_parser::diff::{diff, SideKey, SnapshotDiff};
+use armbankrate_parser::snapshot::{QuoteKey, Snapshot};
+use armbankrate_parser::validation::Side;
+use clap::ArgEnum;
+use colored::Colorize;
+use std::fmt::Display;
+use std::path::PathBuf;
+use tabled::builder::Builder;
+use tabled::object::Segment;
+use tabled::{Alignment, Header, Modify, Style};
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum DiffFormat {
+    Table,
+    Json,
+}
+
+pub(crate) async fn handle_diff(
+    old: PathBuf,
+    new: Option<PathBuf>,
+    changed_only: bool,
+    format: DiffFormat,
+) -> Result<()> {
+    let old = Snapshot::from_file(&old)
+        .with_context(|| format!("Failed to read snapshot {}", old.display()))?;
+    let new = match new {
+        Some(new) => Snapshot::from_file(&new)
+            .with_context(|| format!("Failed to read snapshot {}", new.display()))?,
+        None => Snapshot::from_banks(&fetch_banks(&[]).await?).with_context(|| ERR_MSG)?,
+    };
+
+    let diff = diff(&old, &new);
+
+    match format {
+        DiffFormat::Table => println!("{}", build_table(&diff, changed_only)),
+        DiffFormat::Json => println!("{}", diff.to_json().with_context(|| ERR_MSG)?),
+    }
+
+    Ok(())
+}
+
+fn format_change(change: f64, text: String) -> String {
+    match change.partial_cmp(&0.0) {
+        Some(std::cmp::Ordering::Greater) => text.bright_green().to_string(),
+        Some(std::cmp::Ordering::Less) => text.bright_red().to_string(),
+        _ => text,
+    }
+}
+
+fn format_key(key: &QuoteKey) -> String {
+    format!(
+        "{} {} {}",
+        i18n::bank_name(key.bank()),
+        rate_type_name(key.rate_type()),
+        key.currency()
+    )
+}
+
+fn format_side_key(side_key: &SideKey) -> String {
+    format!(
+        "{} {}",
+        format_key(side_key.key()),
+        side_name(side_key.side())
+    )
+}
+
+fn side_name(side: Side) -> String {
+    match side {
+        Side::Buy => i18n::tr("side.buy"),
+        Side::Sell => i18n::tr("side.sell"),
+    }
+}
+
+/// Translated name of a rate type stored in a snapshot, e.g. `noncash`.
+fn rate_type_name(rate_type: &str) -> String {
+    i18n::tr(&format!("type.{rate_type}"))
+}
+
+fn build_table(diff: &SnapshotDiff, changed_only: bool) -> String {
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns([
+            "bank",
+            "type",
+            "currency",
+            "side",
+            "before",
+            "after",
+            "change",
+            "change_percent",
+        ])
+        .map(|column| column.as_str().colorized()),
+    );
+
+    for change in diff.changes() {
+        if changed_only && change.change() == 0.0 {
+            continue;
+        }
+
+        builder.add_record([
+            i18n::bank_name(change.key().bank())
+                .bright_yellow()
+                .bold()
+                .to_string(),
+            rate_type_name(change.key().rate_type()),
+            change.key().currency().to_string(),
+            side_name(change.side()),
+            i18n::format_number(change.before()),
+            i18n::format_number(change.after()),
+            format_change(change.change(), i18n::format_signed(change.change(), 2)),
+            match change.percent() {
+                Some(percent) => format_change(
+                    change.change(),
+                    format!("{}%", i18n::format_signed(percent, 2)),
+                ),
+                None => MISSING.to_string(),
+            },
+        ]);
+    }
+
+    let mut output = builder
+        .build()
+        .with(Header(
+            i18n::tr("title.changes").bold().bright_green().to_string(),
+        ))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended())
+        .to_string();
+
+    for (title, banks) in [
+        ("diff.added_banks", diff.added_banks()),
+        ("diff.missing_banks", diff.missing_banks()),
+    ] {
+        if !banks.is_empty() {
+            let banks: Vec<String> = banks.iter().map(|bank| i18n::bank_name(bank)).collect();
+            output.push_str(&format!(
+                "\n{}: {}",
+                i18n::tr(title).as_str().colorized(),
+                banks.join(", ")
+            ));
+        }
+    }
+
+    for (title, keys, sides) in [
+        ("diff.appeared", diff.appeared(), diff.appeared_sides()),
+        ("diff.vanished", diff.vanished(), diff.vanished_sides()),
+    ] {
+        if !keys.is_empty() || !sides.is_empty() {
+            let keys: Vec<String> = keys
+                .iter()
+                .map(format_key)
+                .chain(sides.iter().map(format_side_key))
+                .collect();
+            output.push_str(&format!(
+                "\n{}: {}",
+                i18n::tr(title).as_str().colorized(),
+                keys.join(", ")
+            ));
+        }
+    }
+
+    output
+}
@@ -0,0 +1,356 @@
+use crate::{fetch_banks, i18n, rate_type_name, Banks};
+use anyhow::{Context, Result};
+use armbankrate_parser::best::best_rates;
+use armbankrate_parser::validation::Side;
+use armbankrate_parser::{Bank, BankImpl, CurrencyName, CurrencyType};
+use rust_xlsxwriter::{Color, Format, FormatAlign, Workbook, Worksheet, XlsxError};
+use std::path::PathBuf;
+
+const CURRENCIES: [CurrencyName; 4] = [
+    CurrencyName::USD,
+    CurrencyName::EUR,
+    CurrencyName::RUB,
+    CurrencyName::GBP,
+];
+
+const RATE_FORMAT: &str = "#,##0.00##";
+/// Longest worksheet name Excel accepts.
+const MAX_SHEET_NAME: usize = 31;
+const PERCENT_FORMAT: &str = "0.00%";
+
+struct Formats {
+    header: Format,
+    rate: Format,
+    percent: Format,
+}
+
+impl Default for Formats {
+    fn default() -> Self {
+        Self {
+            header: Format::new()
+                .set_bold()
+                .set_align(FormatAlign::Center)
+                .set_background_color(Color::RGB(0xD9E1F2)),
+            rate: Format::new().set_num_format(RATE_FORMAT),
+            percent: Format::new().set_num_format(PERCENT_FORMAT),
+        }
+    }
+}
+
+pub(crate) async fn handle_export(banks: Vec<Banks>, xlsx: PathBuf) -> Result<()> {
+    let banks = fetch_banks(&banks).await?;
+
+    build_workbook(&banks)
+        .and_then(|mut workbook| workbook.save(&xlsx))
+        .with_context(|| format!("Failed to write workbook {}", xlsx.display()))?;
+
+    println!("{}", i18n::tr_with("export.done", xlsx.display()));
+
+    Ok(())
+}
+
+fn build_workbook(banks: &[Bank]) -> Result<Workbook, XlsxError> {
+    let formats = Formats::default();
+    let mut workbook = Workbook::new();
+    let mut sheet_names = Vec::new();
+
+    for currency_type in CurrencyType::ALL {
+        let reported = banks.iter().any(|bank| bank.has_currencies(&currency_type));
+        // Cash and non-cash sheets are always there, other rate types only when reported
+        if !reported && !matches!(currency_type, CurrencyType::Cash | CurrencyType::Noncash) {
+            continue;
+        }
+
+        write_rates(
+            workbook.add_worksheet().set_name(sheet_name(
+                &rate_type_name(&currency_type),
+                currency_type.key(),
+                &mut sheet_names,
+            ))?,
+            banks,
+            &currency_type,
+            &formats,
+        )?;
+    }
+    write_summary(
+        workbook.add_worksheet().set_name(sheet_name(
+            &i18n::tr("export.summary"),
+            "summary",
+            &mut sheet_names,
+        ))?,
+        banks,
+        &formats,
+    )?;
+
+    Ok(workbook)
+}
+
+/// Turns a translated name into a worksheet name Excel accepts, unique among `used`.
+///
+/// Characters Excel forbids are replaced, long names are cut and repeated ones numbered,
+/// `fallback` is used when nothing is left of the name.
+fn sheet_name(name: &str, fallback: &str, used: &mut Vec<String>) -> String {
+    let name: String = name
+        .chars()
+        .map(|char| match char {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            char if char.is_control() => '_',
+            char => char,
+        })
+        .collect();
+    let name = match name.trim().trim_matches('\'') {
+        "" => fallback,
+        name => name,
+    };
+
+    let mut number = 1;
+    let unique = loop {
+        let suffix = match number {
+            1 => String::new(),
+            number => format!(" ({number})"),
+        };
+        let stem: String = name
+            .chars()
+            .take(MAX_SHEET_NAME - suffix.chars().count())
+            .collect();
+        let candidate = format!("{}{suffix}", stem.trim_end());
+
+        if !used
+            .iter()
+            .any(|used| used.to_lowercase() == candidate.to_lowercase())
+        {
+            break candidate;
+        }
+        number += 1;
+    };
+
+    used.push(unique.clone());
+    unique
+}
+
+fn write_rate(
+    worksheet: &mut Worksheet,
+    row: u32,
+    column: u16,
+    rate: &Option<f64>,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    if let Some(rate) = rate {
+        worksheet.write_number_with_format(row, column, *rate, format)?;
+    }
+
+    Ok(())
+}
+
+/// Banks as rows, a buy and a sell column for every currency.
+fn write_rates(
+    worksheet: &mut Worksheet,
+    banks: &[Bank],
+    currency_type: &CurrencyType,
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    let [bank, buy, sell] = i18n::columns(["bank", "buy", "sell"]);
+    worksheet.merge_range(0, 0, 1, 0, &bank, &formats.header)?;
+    worksheet.set_column_width(0, 16)?;
+
+    for (index, currency_name) in CURRENCIES.iter().enumerate() {
+        let column = 1 + index as u16 * 2;
+        worksheet.merge_range(
+            0,
+            column,
+            0,
+            column + 1,
+            &format!("{currency_name:?}"),
+            &formats.header,
+        )?;
+        worksheet.write_string_with_format(1, column, &buy, &formats.header)?;
+        worksheet.write_string_with_format(1, column + 1, &sell, &formats.header)?;
+        worksheet.set_column_width(column, 11)?;
+        worksheet.set_column_width(column + 1, 11)?;
+    }
+
+    for (index, bank) in banks.iter().enumerate() {
+        let row = 2 + index as u32;
+        worksheet.write_string(row, 0, i18n::bank_name(bank.get_name()))?;
+
+        for (currency_index, currency_name) in CURRENCIES.iter().enumerate() {
+            let column = 1 + currency_index as u16 * 2;
+            let currency = bank.currencies(currency_type).get_rate(currency_name);
+
+            write_rate(worksheet, row, column, currency.buy(), &formats.rate)?;
+            write_rate(worksheet, row, column + 1, currency.sell(), &formats.rate)?;
+        }
+    }
+
+    worksheet.set_freeze_panes(2, 1)?;
+
+    Ok(())
+}
+
+/// Best rates of every currency followed by the spread of every bank.
+fn write_summary(
+    worksheet: &mut Worksheet,
+    banks: &[Bank],
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    let columns = i18n::columns([
+        "type",
+        "currency",
+        "best_buy",
+        "buy_banks",
+        "best_sell",
+        "sell_banks",
+        "spread",
+    ]);
+    for (column, title) in columns.iter().enumerate() {
+        worksheet.write_string_with_format(0, column as u16, title, &formats.header)?;
+        worksheet.set_column_width(column as u16, 16)?;
+    }
+
+    let best_rates = best_rates(banks, 1);
+    let mut row = 1;
+    // Buy and sell rates of a currency come in pairs
+    for pair in best_rates.chunks(2) {
+        let (buy, sell) = match pair {
+            [buy, sell] if buy.side() == Side::Buy => (buy, sell),
+            _ => continue,
+        };
+        let (buy, sell) = (buy.best(), sell.best());
+
+        worksheet.write_string(row, 0, rate_type_name(pair[0].currency_type()))?;
+        worksheet.write_string(row, 1, format!("{:?}", pair[0].currency_name()))?;
+        if let Some(buy) = buy {
+            worksheet.write_number_with_format(row, 2, buy.rate(), &formats.rate)?;
+            worksheet.write_string(row, 3, bank_names(buy.banks()))?;
+        }
+        if let Some(sell) = sell {
+            worksheet.write_number_with_format(row, 4, sell.rate(), &formats.rate)?;
+            worksheet.write_string(row, 5, bank_names(sell.banks()))?;
+        }
+        if let (Some(buy), Some(sell)) = (buy, sell) {
+            worksheet.write_number_with_format(row, 6, sell.rate() - buy.rate(), &formats.rate)?;
+        }
+        row += 1;
+    }
+
+    row += 1;
+    let columns = i18n::columns([
+        "bank",
+        "type",
+        "currency",
+        "buy",
+        "sell",
+        "spread",
+        "spread_percent",
+    ]);
+    for (column, title) in columns.iter().enumerate() {
+        worksheet.write_string_with_format(row, column as u16, title, &formats.header)?;
+    }
+    row += 1;
+
+    for currency_type in CurrencyType::ALL {
+        for bank in banks {
+            for currency_name in &CURRENCIES {
+                let currency = bank.currencies(&currency_type).get_rate(currency_name);
+                let (buy, sell) = match (currency.buy(), currency.sell()) {
+                    (Some(buy), Some(sell)) if *buy > 0.0 && *sell > 0.0 => (*buy, *sell),
+                    _ => continue,
+                };
+
+                worksheet.write_string(row, 0, i18n::bank_name(bank.get_name()))?;
+                worksheet.write_string(row, 1, rate_type_name(&currency_type))?;
+                worksheet.write_string(row, 2, format!("{currency_name:?}"))?;
+                worksheet.write_number_with_format(row, 3, buy, &formats.rate)?;
+                worksheet.write_number_with_format(row, 4, sell, &formats.rate)?;
+                worksheet.write_number_with_format(row, 5, sell - buy, &formats.rate)?;
+                worksheet.write_number_with_format(row, 6, (sell - buy) / buy, &formats.percent)?;
+                row += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn bank_names(banks: &[String]) -> String {
+    let names: Vec<String> = banks.iter().map(|bank| i18n::bank_name(bank)).collect();
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use armbankrate_parser::Currency;
+    use std::io::Read;
+    use std::str::FromStr;
+
+    fn read(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> String {
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn sheet_names_are_valid_and_unique() {
+        let mut used = Vec::new();
+
+        assert_eq!(sheet_name("Cash", "cash", &mut used), "Cash");
+        assert_eq!(sheet_name("cash", "cash", &mut used), "cash (2)");
+        assert_eq!(
+            sheet_name("Card/Transfer: [all]?", "card", &mut used),
+            "Card_Transfer_ _all__"
+        );
+        assert_eq!(sheet_name(" '' ", "summary", &mut used), "summary");
+
+        let long = "Безналичный расчёт по банковским картам";
+        let first = sheet_name(long, "noncash", &mut used);
+        let second = sheet_name(long, "noncash", &mut used);
+        assert_eq!(first.chars().count(), MAX_SHEET_NAME);
+        assert_eq!(second.chars().count(), MAX_SHEET_NAME);
+        assert!(second.ends_with(" (2)"));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn workbook_is_written() {
+        let mut bank = Bank::from_str("idbank").unwrap();
+        bank.cash_currencies_mut().fill_from_currency(Currency::new(
+            CurrencyName::USD,
+            Some(385.5),
+            Some(391.0),
+        ));
+
+        let dir = std::env::temp_dir().join(format!("armbankrate-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rates.xlsx");
+        build_workbook(&[bank]).unwrap().save(&path).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let workbook = read(&mut archive, "xl/workbook.xml");
+        let cash = read(&mut archive, "xl/worksheets/sheet1.xml");
+        let strings = read(&mut archive, "xl/sharedStrings.xml");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let sheets: Vec<&str> = workbook
+            .split("<sheet name=\"")
+            .skip(1)
+            .filter_map(|sheet| sheet.split('"').next())
+            .collect();
+        assert_eq!(
+            sheets,
+            [
+                rate_type_name(&CurrencyType::Cash),
+                rate_type_name(&CurrencyType::Noncash),
+                i18n::tr("export.summary"),
+            ]
+        );
+
+        assert!(strings.contains(&i18n::bank_name("Idbank")));
+        assert!(cash.contains("<v>385.5</v>"));
+        assert!(cash.contains("<v>391</v>"));
+    }
+}
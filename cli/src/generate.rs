@@ -0,0 +1,22 @@
+use crate::Cli;
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+
+/// Prints a completion script for `shell`, generated from the clap definitions.
+pub(crate) fn handle_completions(shell: Shell) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+/// Prints a roff man page, e.g. `armbankrate man > armbankrate.1`.
+pub(crate) fn handle_man() -> Result<()> {
+    let mut buffer = Vec::new();
+    clap_mangen::Man::new(Cli::command()).render(&mut buffer)?;
+
+    std::io::stdout().write_all(&buffer)?;
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable pointing to a directory with additional `<lang>.properties` catalogues.
+pub(crate) const LOCALE_DIR_ENV: &str = "ARMBANKRATE_LOCALE_DIR";
+
+const FALLBACK: &str = "en";
+
+const EMBEDDED: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.properties")),
+    ("hy", include_str!("../locales/hy.properties")),
+    ("ru", include_str!("../locales/ru.properties")),
+];
+
+static CATALOGUE: OnceLock<Catalogue> = OnceLock::new();
+
+/// Messages of the selected language, missing keys fall back to English.
+#[derive(Debug, Default)]
+pub(crate) struct Catalogue {
+    lang: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalogue {
+    /// Loads the catalogue for `lang`, or for the language of the locale environment variables.
+    ///
+    /// Files named `<lang>.properties` in `dir` override embedded messages,
+    /// so a language can be added or adjusted without rebuilding.
+    pub(crate) fn load(lang: Option<&str>, dir: Option<&Path>) -> Self {
+        let lang = lang.map(str::to_lowercase).unwrap_or_else(detect_lang);
+        let mut catalogue = Catalogue {
+            lang: lang.clone(),
+            ..Default::default()
+        };
+
+        for lang in [FALLBACK, lang.as_str()] {
+            if let Some((_, source)) = EMBEDDED.iter().find(|(name, _)| *name == lang) {
+                catalogue.extend(source);
+            }
+            if let Some(source) = dir.and_then(|dir| {
+                std::fs::read_to_string(dir.join(format!("{lang}.properties"))).ok()
+            }) {
+                catalogue.extend(&source);
+            }
+        }
+
+        catalogue
+    }
+
+    /// Parses `key = value` lines, values may be wrapped in double quotes to keep surrounding spaces.
+    fn extend(&mut self, source: &str) {
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .unwrap_or(value);
+                self.messages
+                    .insert(key.trim().to_string(), value.to_string());
+            }
+        }
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// Formats a rate with the decimal and thousands separators of the language.
+    fn format_number(&self, value: f64) -> String {
+        self.localize_number(value, &value.abs().to_string())
+    }
+
+    /// Formats a value rounded to `decimals` places, with a `+` sign when it is positive.
+    fn format_signed(&self, value: f64, decimals: usize) -> String {
+        // The sign follows the rounded value, so tiny changes do not show as -0.00
+        let rounded = format!("{value:.decimals$}").parse().unwrap_or(value);
+        let formatted = self.localize_number(rounded, &format!("{:.decimals$}", rounded.abs()));
+
+        match rounded > 0.0 {
+            true => format!("+{formatted}"),
+            false => formatted,
+        }
+    }
+
+    /// Puts the separators of the language into `formatted`, the absolute value of `value`.
+    fn localize_number(&self, value: f64, formatted: &str) -> String {
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted, None),
+        };
+
+        let thousands = self.get("number.thousands");
+        let mut output = String::new();
+        if value.is_sign_negative() && value != 0.0 {
+            output.push('-');
+        }
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                output.push_str(thousands);
+            }
+            output.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            output.push_str(self.get("number.decimal"));
+            output.push_str(fraction);
+        }
+
+        output
+    }
+}
+
+/// Takes the language from `LC_ALL`, `LC_MESSAGES` or `LANG`, e.g. `hy_AM.UTF-8` gives `hy`.
+fn detect_lang() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| value.split(['_', '.', '@']).next().map(str::to_lowercase))
+        .filter(|lang| lang != "c" && lang != "posix")
+        .unwrap_or_else(|| FALLBACK.to_string())
+}
+
+/// Sets the catalogue used for the output, has no effect after the first call.
+pub(crate) fn init(lang: Option<&str>, dir: Option<PathBuf>) {
+    let dir = dir.or_else(|| std::env::var_os(LOCALE_DIR_ENV).map(PathBuf::from));
+    CATALOGUE.get_or_init(|| Catalogue::load(lang, dir.as_deref()));
+}
+
+fn catalogue() -> &'static Catalogue {
+    CATALOGUE.get_or_init(|| Catalogue::load(None, None))
+}
+
+/// Code of the selected language, e.g. `hy`.
+pub(crate) fn lang() -> String {
+    catalogue().lang.clone()
+}
+
+/// Translated message for `key`, the key itself when no catalogue has it.
+pub(crate) fn tr(key: &str) -> String {
+    catalogue().get(key).to_string()
+}
+
+/// Translated message with `{}` replaced by `argument`.
+pub(crate) fn tr_with(key: &str, argument: impl std::fmt::Display) -> String {
+    tr_args(key, &[&argument])
+}
+
+/// Translated message with every `{}` replaced by the next of `arguments`.
+pub(crate) fn tr_args(key: &str, arguments: &[&dyn std::fmt::Display]) -> String {
+    arguments
+        .iter()
+        .fold(catalogue().get(key).to_string(), |message, argument| {
+            message.replacen("{}", &argument.to_string(), 1)
+        })
+}
+
+/// Translated titles of table columns, `column.<key>` in the catalogues.
+pub(crate) fn columns<const N: usize>(keys: [&str; N]) -> [String; N] {
+    keys.map(|key| tr(&format!("column.{key}")))
+}
+
+pub(crate) fn bank_name(name: &str) -> String {
+    let key = format!("bank.{name}");
+    match catalogue().get(&key) {
+        translated if translated == key => name.to_string(),
+        translated => translated.to_string(),
+    }
+}
+
+pub(crate) fn currency_name(name: &armbankrate_parser::CurrencyName) -> String {
+    let code = format!("{name:?}");
+    let key = format!("currency.{code}");
+    match catalogue().get(&key) {
+        translated if translated == key => code,
+        translated => translated.to_string(),
+    }
+}
+
+/// Formats a rate with the decimal and thousands separators of the language.
+pub(crate) fn format_number(value: f64) -> String {
+    catalogue().format_number(value)
+}
+
+/// Formats a change rounded to `decimals` places, with the separators of the language and a sign.
+pub(crate) fn format_signed(value: f64, decimals: usize) -> String {
+    catalogue().format_signed(value, decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_grouped_by_thousands() {
+        let catalogue = Catalogue::load(Some("en"), None);
+
+        assert_eq!(catalogue.format_number(0.0), "0");
+        assert_eq!(catalogue.format_number(999.0), "999");
+        assert_eq!(catalogue.format_number(1000.0), "1,000");
+        assert_eq!(catalogue.format_number(1234567.0), "1,234,567");
+    }
+
+    #[test]
+    fn decimals_use_the_separator_of_the_language() {
+        let catalogue = Catalogue::load(Some("en"), None);
+        assert_eq!(catalogue.format_number(386.5), "386.5");
+        assert_eq!(catalogue.format_number(12345.25), "12,345.25");
+
+        let catalogue = Catalogue::load(Some("hy"), None);
+        assert_eq!(catalogue.format_number(386.5), "386,5");
+        assert_eq!(catalogue.format_number(12345.25), "12 345,25");
+    }
+
+    #[test]
+    fn negative_numbers_keep_their_sign() {
+        let catalogue = Catalogue::load(Some("en"), None);
+
+        assert_eq!(catalogue.format_number(-4.25), "-4.25");
+        assert_eq!(catalogue.format_number(-1500.0), "-1,500");
+        assert_eq!(catalogue.format_number(-0.0), "0");
+    }
+
+    #[test]
+    fn signed_numbers_are_rounded() {
+        let catalogue = Catalogue::load(Some("en"), None);
+        assert_eq!(catalogue.format_signed(1.5, 2), "+1.50");
+        assert_eq!(catalogue.format_signed(-1234.567, 2), "-1,234.57");
+        assert_eq!(catalogue.format_signed(0.0, 2), "0.00");
+        assert_eq!(catalogue.format_signed(-0.001, 2), "0.00");
+
+        let catalogue = Catalogue::load(Some("hy"), None);
+        assert_eq!(catalogue.format_signed(2.25, 2), "+2,25");
+    }
+
+    #[test]
+    fn extend_parses_properties() {
+        let mut catalogue = Catalogue::default();
+        catalogue
+            .extend("# comment\n\n  header.bank = Bank  \nnumber.thousands = \" \"\nbroken line\n");
+
+        assert_eq!(catalogue.get("header.bank"), "Bank");
+        assert_eq!(catalogue.get("number.thousands"), " ");
+        assert_eq!(catalogue.messages.len(), 2);
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english() {
+        let dir = std::env::temp_dir().join(format!("armbankrate-locales-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("xx.properties"), "header.bank = Xank\n").unwrap();
+
+        let catalogue = Catalogue::load(Some("XX"), Some(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(catalogue.lang, "xx");
+        assert_eq!(catalogue.get("header.bank"), "Xank");
+        assert_eq!(catalogue.get("header.currency"), "Currency");
+        assert_eq!(catalogue.get("no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn catalogues_translate_over_english() {
+        let catalogue = Catalogue::load(Some("ru"), None);
+
+        assert_eq!(catalogue.get("header.bank"), "Банк");
+        assert_eq!(catalogue.get("column.etag"), "ETag");
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{format_rate, i18n, metals, rate_type_name, Banks, Colorized, ERR_MSG, MISSING};
+use anyhow::{bail, Context, Result};
+use armbankrate_parser::stream::{parse_stream, BankOutcome};
+use armbankrate_parser::{BankImpl, CurrencyName};
+use chrono::{DateTime, Local, Utc};
+use clap::ArgEnum;
+use colored::Colorize;
+use futures::StreamExt;
+use std::fmt::Display;
+use tabled::builder::Builder;
+use tabled::object::{Columns, Segment};
+use tabled::{Alignment, Header, Modify, Style};
+
+const CURRENCIES: [CurrencyName; 4] = [
+    CurrencyName::USD,
+    CurrencyName::EUR,
+    CurrencyName::RUB,
+    CurrencyName::GBP,
+];
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum InfoFormat {
+    Table,
+    Json,
+}
+
+/// Prints the metadata of a bank followed by its current rates and how fetching them went.
+pub(crate) async fn handle_info(bank: Banks, format: InfoFormat) -> Result<()> {
+    if bank == Banks::All {
+        bail!("Choose a single bank, e.g. `armbankrate info inecobank`");
+    }
+
+    let outcome = parse_stream(&[bank])
+        .with_context(|| ERR_MSG)?
+        .next()
+        .await
+        .with_context(|| ERR_MSG)?;
+
+    match format {
+        InfoFormat::Json => println!("{}", outcome.to_detail_json().with_context(|| ERR_MSG)?),
+        InfoFormat::Table => {
+            println!("{}", build_info(&outcome));
+            if outcome.is_ok() {
+                println!("{}", build_rates(&outcome));
+                if let Some(table) = metals::build_table(std::slice::from_ref(outcome.bank()), &[])
+                {
+                    println!("{table}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn build_info(outcome: &BankOutcome) -> String {
+    let bank = outcome.bank();
+    let info = bank.info();
+    let or_missing = |value: &str| match value.is_empty() {
+        true => MISSING.to_string(),
+        false => value.to_string(),
+    };
+
+    let status = match outcome.error() {
+        None => i18n::tr_with("info.ok", outcome.elapsed().as_millis())
+            .bright_green()
+            .to_string(),
+        Some(error) => i18n::tr_with("info.failed", error).bright_red().to_string(),
+    };
+    let cash = bank.cash_currencies();
+    let rate_types: Vec<String> = info.rate_types().iter().map(rate_type_name).collect();
+
+    let mut builder = Builder::default();
+    for (key, value) in [
+        ("legal_name", or_missing(info.legal_name())),
+        ("code", or_missing(info.code())),
+        ("swift", or_missing(info.swift())),
+        ("website", or_missing(info.website())),
+        ("rates_page", or_missing(info.rates_page())),
+        ("source", or_missing(info.url())),
+        ("address", or_missing(info.address())),
+        ("phone", or_missing(info.phone())),
+        ("rate_types", rate_types.join(", ")),
+        ("status", status),
+        ("fetched_at", format_time(cash.fetched_at())),
+    ] {
+        builder.add_record([i18n::tr(&format!("info.{key}")).as_str().colorized(), value]);
+    }
+
+    builder
+        .build()
+        .with(Header(
+            i18n::bank_name(bank.get_name())
+                .bold()
+                .bright_yellow()
+                .to_string(),
+        ))
+        .with(Modify::new(Columns::single(0)).with(Alignment::left()))
+        .with(Style::extended())
+        .to_string()
+}
+
+/// Every rate category the bank published, with spreads and amount tiers.
+fn build_rates(outcome: &BankOutcome) -> String {
+    let bank = outcome.bank();
+
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns([
+            "type",
+            "currency",
+            "buy",
+            "sell",
+            "spread",
+            "spread_percent",
+        ])
+        .map(|column| column.as_str().colorized()),
+    );
+
+    for currency_type in bank.currency_types() {
+        for currency_name in &CURRENCIES {
+            let currency = bank.currencies(&currency_type).get_rate(currency_name);
+            builder.add_record(rate_record(
+                rate_type_name(&currency_type),
+                i18n::currency_name(currency_name),
+                currency.buy(),
+                currency.sell(),
+            ));
+
+            for tier in currency.tiers() {
+                builder.add_record(rate_record(
+                    String::new(),
+                    format!("≥ {}", i18n::format_number(tier.min_amount())),
+                    tier.buy(),
+                    tier.sell(),
+                ));
+            }
+        }
+    }
+
+    builder
+        .build()
+        .with(Header(
+            i18n::tr("title.rates").bold().bright_green().to_string(),
+        ))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended())
+        .to_string()
+}
+
+fn rate_record(
+    currency_type: String,
+    currency: String,
+    buy: &Option<f64>,
+    sell: &Option<f64>,
+) -> [String; 6] {
+    let (spread, spread_percent) = match (buy, sell) {
+        (Some(buy), Some(sell)) if *buy > 0.0 => (
+            i18n::format_number(((sell - buy) * 100.0).round() / 100.0),
+            format!("{:.2}%", (sell - buy) / buy * 100.0),
+        ),
+        _ => (MISSING.to_string(), MISSING.to_string()),
+    };
+
+    [
+        currency_type,
+        currency,
+        format_rate(buy).bright_green().to_string(),
+        format_rate(sell).bright_red().to_string(),
+        spread,
+        spread_percent,
+    ]
+}
+
+fn format_time(time: &Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        None => MISSING.to_string(),
+    }
+}
@@ -0,0 +1,41 @@
+use clap::{ArgEnum, Args};
+use std::fmt::Display;
+use tracing_subscriber::EnvFilter;
+
+#[derive(ArgEnum, Display, Debug, Clone, Copy)]
+pub(crate) enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LogArgs {
+    /// Log bank timings (-v), requests and parsed rows (-vv) or everything (-vvv) to stderr
+    #[clap(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    #[clap(value_enum, long, global = true, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+impl LogArgs {
+    /// Installs the subscriber, `RUST_LOG` takes precedence over the verbosity flags.
+    pub(crate) fn init(&self, ansi: bool) {
+        let level = match self.verbose {
+            0 => "error",
+            1 => "armbankrate_parser=info",
+            2 => "armbankrate_parser=debug",
+            _ => "trace",
+        };
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .with_ansi(ansi);
+
+        match self.log_format {
+            LogFormat::Text => subscriber.init(),
+            LogFormat::Json => subscriber.json().init(),
+        }
+    }
+}
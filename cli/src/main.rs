@@ -1,15 +1,50 @@
+mod best;
+mod branches;
+mod cache;
+mod chart;
+mod convert;
+mod diff;
+mod export;
+mod generate;
+mod i18n;
+mod info;
+mod logging;
+mod metals;
+mod report;
+mod stream;
 mod table_builder;
+mod throttle;
+mod tui;
+mod watch;
 
 #[macro_use]
 extern crate enum_display_derive;
 
+use crate::best::BestFormat;
+use crate::branches::BranchesFormat;
+use crate::cache::{CacheArgs, CacheCommand};
+use crate::chart::ChartStyle;
+use crate::convert::{ConvertFormat, ExchangeSide};
+use crate::diff::DiffFormat;
+use crate::info::InfoFormat;
+use crate::logging::LogArgs;
+use crate::metals::MetalsFormat;
+use crate::report::{OutputFormat, Report};
+use crate::stream::StreamFormat;
 use crate::table_builder::TableBuilder;
+use crate::throttle::ThrottleArgs;
 use anyhow::{Context, Result};
-use armbankrate_parser::sort::{OrderType, SortData};
-use armbankrate_parser::Currency;
-use clap::{ArgEnum, Parser, Subcommand};
+use armbankrate_parser::layout::Layout;
+use armbankrate_parser::sort::{Direction, Missing, OrderType, SortData};
+use armbankrate_parser::validation::{Side, Verdict};
+use armbankrate_parser::{Bank, BankImpl, Currency};
+use clap::{ArgEnum, Args, Parser, Subcommand};
 use colored::Colorize;
 use std::fmt::Display;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 static ERR_MSG: &str = "Something went wrong while receiving bank rates";
 
@@ -29,6 +64,37 @@ enum CurrencyType {
     All,
     Cash,
     Noncash,
+    Card,
+    Transfer,
+}
+
+impl CurrencyType {
+    /// Rate types selected by the argument, `All` selects every one.
+    pub(crate) fn rate_types(&self) -> Vec<armbankrate_parser::CurrencyType> {
+        match self {
+            CurrencyType::All => armbankrate_parser::CurrencyType::ALL.to_vec(),
+            CurrencyType::Cash => vec![armbankrate_parser::CurrencyType::Cash],
+            CurrencyType::Noncash => vec![armbankrate_parser::CurrencyType::Noncash],
+            CurrencyType::Card => vec![armbankrate_parser::CurrencyType::Card],
+            CurrencyType::Transfer => vec![armbankrate_parser::CurrencyType::Transfer],
+        }
+    }
+
+    /// Rate types to show for `banks`, `All` leaves out the ones no bank publishes.
+    pub(crate) fn shown_rate_types(&self, banks: &[Bank]) -> Vec<armbankrate_parser::CurrencyType> {
+        self.rate_types()
+            .into_iter()
+            .filter(|currency_type| {
+                !matches!(self, CurrencyType::All)
+                    || banks.iter().any(|bank| bank.has_currencies(currency_type))
+            })
+            .collect()
+    }
+}
+
+/// Translated name of a rate type, e.g. `Non-cash`.
+pub(crate) fn rate_type_name(currency_type: &armbankrate_parser::CurrencyType) -> String {
+    i18n::tr(&format!("type.{}", currency_type.key()))
 }
 
 #[derive(Subcommand, Debug)]
@@ -39,27 +105,327 @@ enum Commands {
         currency_type: CurrencyType,
         #[clap(value_parser)]
         banks: Vec<Banks>,
-        #[clap(value_enum, long, short)]
-        sort: Option<CurrencyName>,
+        #[clap(flatten)]
+        table: TableArgs,
+        /// Flag rates older than the given age (e.g. 30m, 2h, 1day)
+        #[clap(long, value_parser = humantime::parse_duration)]
+        max_age: Option<Duration>,
+        /// Hide rates older than --max-age instead of flagging them
+        #[clap(long, action, requires = "max-age")]
+        exclude_stale: bool,
+        #[clap(value_enum, long, short, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+        /// Write the output to a file instead of printing it
+        #[clap(long, short, value_parser)]
+        output: Option<PathBuf>,
+    },
+    /// Periodically re-parses banks and highlights changed rates
+    Watch {
+        #[clap(value_enum, default_value_t = CurrencyType::All)]
+        currency_type: CurrencyType,
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        #[clap(flatten)]
+        table: TableArgs,
+        /// Time between polls (e.g. 30s, 5m)
+        #[clap(long, short, default_value = "1m", value_parser = humantime::parse_duration)]
+        interval: Duration,
+    },
+    /// Interactive terminal UI for exploring rates (banks can be selected)
+    Tui {
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+    },
+    /// Shows the banks with the best buy and sell rates for each currency
+    Best {
+        #[clap(value_enum, default_value_t = CurrencyType::All)]
+        currency_type: CurrencyType,
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        /// Show only the given currency, can be repeated (e.g. -c usd -c eur)
+        #[clap(long = "currency", short = 'c', value_parser)]
+        currencies: Vec<armbankrate_parser::CurrencyName>,
+        /// Number of ranks to show for each side
+        #[clap(long, short, default_value_t = 1, value_parser = parse_count)]
+        top: usize,
+        #[clap(value_enum, long, short, default_value_t = BestFormat::Table)]
+        format: BestFormat,
+    },
+    /// Converts an amount of a currency to AMD at every bank's rate, best deal first
+    Convert {
+        /// Amount of the currency, picks the rate tier of banks quoting larger amounts separately
+        #[clap(value_parser = parse_amount)]
+        amount: f64,
+        #[clap(value_parser)]
+        currency: armbankrate_parser::CurrencyName,
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        /// Whether you sell the currency to the bank or buy it
+        #[clap(value_enum, long, default_value_t = ExchangeSide::Sell)]
+        side: ExchangeSide,
+        #[clap(value_enum, long = "type", default_value_t = CurrencyType::Cash)]
+        currency_type: CurrencyType,
+        #[clap(value_enum, long, short, default_value_t = ConvertFormat::Table)]
+        format: ConvertFormat,
+    },
+    /// Shows the details of a bank with all of its current rates and the fetch status
+    Info {
+        #[clap(value_enum)]
+        bank: Banks,
+        #[clap(value_enum, long, short, default_value_t = InfoFormat::Table)]
+        format: InfoFormat,
+    },
+    /// Shows gold and silver bullion prices of the banks that publish them
+    Metals {
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        /// Show only the given metal, can be repeated (e.g. -m gold)
+        #[clap(long = "metal", short = 'm', value_parser)]
+        metals: Vec<armbankrate_parser::metals::Metal>,
+        #[clap(value_enum, long, short, default_value_t = MetalsFormat::Table)]
+        format: MetalsFormat,
+    },
+    /// Lists bank branches, or the best cash rates of a currency with the branches they apply at
+    Branches {
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        /// Show the best cash rates of the currency instead of the branch list (e.g. --best usd)
+        #[clap(long, value_parser)]
+        best: Option<armbankrate_parser::CurrencyName>,
+        /// Number of rates to show for each side
+        #[clap(long, short, default_value_t = 5, value_parser = parse_count)]
+        top: usize,
+        #[clap(value_enum, long, short, default_value_t = BranchesFormat::Table)]
+        format: BranchesFormat,
+    },
+    /// Prints each bank as soon as its rates arrive, followed by a summary of failures and timing
+    Stream {
+        #[clap(value_enum, default_value_t = CurrencyType::All)]
+        currency_type: CurrencyType,
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        /// Show only the given currency, can be repeated (e.g. -c usd -c eur)
+        #[clap(long = "currency", short = 'c', value_parser)]
+        currencies: Vec<armbankrate_parser::CurrencyName>,
+        #[clap(value_enum, long, short, default_value_t = StreamFormat::Rows)]
+        format: StreamFormat,
+    },
+    /// Compares two JSON snapshots, or a snapshot with the current rates
+    Diff {
+        /// Older snapshot (output of the json command)
+        #[clap(value_parser)]
+        old: PathBuf,
+        /// Newer snapshot, current rates are fetched when omitted
+        #[clap(value_parser)]
+        new: Option<PathBuf>,
+        /// Hide rates that did not change
+        #[clap(long, action)]
+        changed: bool,
+        #[clap(value_enum, long, short, default_value_t = DiffFormat::Table)]
+        format: DiffFormat,
+    },
+    /// Charts rates over time from a directory of JSON snapshots
+    Chart {
+        /// Directory with snapshots (outputs of the json command)
+        #[clap(value_parser)]
+        dir: PathBuf,
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        #[clap(value_enum, long = "type", default_value_t = CurrencyType::Cash)]
+        currency_type: CurrencyType,
+        /// Currency to chart, can be repeated (defaults to USD)
+        #[clap(long = "currency", short = 'c', value_parser)]
+        currencies: Vec<armbankrate_parser::CurrencyName>,
+        /// Only show snapshots taken within this time (e.g. 7days, 12h)
+        #[clap(long, value_parser = humantime::parse_duration)]
+        since: Option<Duration>,
+        #[clap(value_enum, long, default_value_t = ChartStyle::Sparkline)]
+        style: ChartStyle,
+        /// Height of line charts in rows
+        #[clap(long, default_value_t = 10)]
+        height: usize,
+    },
+    /// Exports rates to a spreadsheet (banks can be selected)
+    Export {
+        #[clap(value_parser)]
+        banks: Vec<Banks>,
+        /// Path of the Excel workbook to write
+        #[clap(long, value_parser)]
+        xlsx: PathBuf,
     },
     /// Parses banks and outputs as JSON (banks can be selected)
     Json {
         #[clap(value_parser)]
         banks: Vec<Banks>,
     },
+    /// Inspects or clears the response cache
+    Cache {
+        #[clap(subcommand)]
+        command: CacheCommand,
+    },
+    /// Prints a shell completion script (e.g. armbankrate completions bash > /etc/bash_completion.d/armbankrate)
+    Completions {
+        #[clap(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Prints the man page in roff format
+    Man,
+}
+
+#[derive(Args, Debug, Clone)]
+pub(crate) struct TableArgs {
+    /// Sort key, can be repeated (e.g. -s usd-buy -s eur-spread:asc)
+    #[clap(long, short, value_parser)]
+    sort: Vec<SortKey>,
+    /// Placement of banks without a rate for the sort key
+    #[clap(value_enum, long, default_value_t = MissingPlacement::Last)]
+    missing: MissingPlacement,
+    /// Show only the given currency, can be repeated (e.g. -c usd -c eur)
+    #[clap(long = "currency", short = 'c', value_parser)]
+    currencies: Vec<armbankrate_parser::CurrencyName>,
+    /// Leave the given bank out, can be repeated
+    #[clap(value_enum, long = "exclude-bank", short = 'x')]
+    exclude_banks: Vec<Banks>,
+    /// Put banks or currencies in rows
+    #[clap(value_enum, long, default_value_t = TableLayout::Banks)]
+    layout: TableLayout,
+    /// Table border style
+    #[clap(value_enum, long, default_value_t = TableStyle::Extended)]
+    style: TableStyle,
+    /// Sort by the rate tiers matching a transaction of this many units (e.g. 5000)
+    #[clap(long, value_parser = parse_amount)]
+    amount: Option<f64>,
+}
+
+impl TableArgs {
+    /// Removes the banks left out with `--exclude-bank`.
+    pub(crate) fn retain_banks(&self, banks: &mut Vec<Bank>) {
+        banks.retain(|bank| {
+            !self
+                .exclude_banks
+                .iter()
+                .any(|excluded| excluded.to_string().eq_ignore_ascii_case(bank.get_name()))
+        });
+    }
+
+    /// Currencies to show, all of them when none were selected.
+    pub(crate) fn currency_names(&self) -> Vec<armbankrate_parser::CurrencyName> {
+        match self.currencies.is_empty() {
+            true => vec![
+                armbankrate_parser::CurrencyName::USD,
+                armbankrate_parser::CurrencyName::EUR,
+                armbankrate_parser::CurrencyName::RUB,
+                armbankrate_parser::CurrencyName::GBP,
+            ],
+            false => self.currencies.clone(),
+        }
+    }
+
+    pub(crate) fn layout(&self) -> Layout {
+        self.layout.into()
+    }
+
+    pub(crate) fn sort_banks(
+        &self,
+        banks: &mut [Bank],
+        currency_type: armbankrate_parser::CurrencyType,
+    ) {
+        if self.sort.is_empty() {
+            return;
+        }
+
+        let sort_data: Vec<SortData> = self
+            .sort
+            .iter()
+            .map(|sort| sort.to_sort_data(currency_type.clone(), self.missing.into()))
+            .map(|sort_data| match self.amount {
+                Some(amount) => sort_data.with_amount(amount),
+                None => sort_data,
+            })
+            .collect();
+        armbankrate_parser::sort::sort_banks_by(banks, &sort_data);
+    }
+}
+
+#[derive(ArgEnum, Display, Debug, Clone, Copy)]
+pub(crate) enum TableStyle {
+    Extended,
+    Ascii,
+    Markdown,
+    Unicode,
+    Compact,
+}
+
+#[derive(ArgEnum, Display, Debug, Clone, Copy)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Colors are used in auto mode when printing to a terminal and `NO_COLOR` is not set.
+    fn enabled(&self, stream: &impl IsTerminal) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && stream.is_terminal()
+            }
+        }
+    }
+}
+
+#[derive(ArgEnum, Display, Debug, Clone, Copy)]
+enum TableLayout {
+    Banks,
+    Currencies,
+}
+
+impl From<TableLayout> for Layout {
+    fn from(layout: TableLayout) -> Self {
+        match layout {
+            TableLayout::Banks => Layout::BankRows,
+            TableLayout::Currencies => Layout::CurrencyRows,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
 #[clap(name = "armbankrate")]
 #[clap(author = "David Eritsyan <dav.eritsyan@gmail.com>")]
 #[clap(version = "0.1.0")]
+#[clap(about)]
 struct Cli {
     #[clap(subcommand)]
     command: Commands,
 
-    /// Output program execution time
+    /// Output program execution time (the stream command always prints it in its summary)
     #[clap(short, long, action)]
     time: bool,
+
+    /// When to use colors
+    #[clap(value_enum, long, global = true, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Output language (en, hy, ru), taken from LC_ALL, LC_MESSAGES or LANG by default
+    #[clap(long, global = true, value_parser)]
+    lang: Option<String>,
+
+    /// Directory with <lang>.properties files overriding the built-in messages
+    /// (defaults to $ARMBANKRATE_LOCALE_DIR)
+    #[clap(long, global = true, value_parser)]
+    locale_dir: Option<PathBuf>,
+
+    #[clap(flatten)]
+    cache: CacheArgs,
+
+    #[clap(flatten)]
+    throttle: ThrottleArgs,
+
+    #[clap(flatten)]
+    log: LogArgs,
 }
 
 #[tokio::main]
@@ -67,17 +433,102 @@ async fn main() -> Result<()> {
     let start = std::time::Instant::now();
 
     let cli: Cli = Cli::parse();
+    colored::control::set_override(cli.color.enabled(&std::io::stdout()));
+    cli.log.init(cli.color.enabled(&std::io::stderr()));
+    i18n::init(cli.lang.as_deref(), cli.locale_dir.clone());
+    cli.cache.apply(matches!(
+        cli.command,
+        Commands::Watch { .. } | Commands::Tui { .. }
+    ));
+    cli.throttle.apply();
+
+    // The stream command ends with its own summary, which includes the time
+    let print_time = cli.time && !matches!(cli.command, Commands::Stream { .. });
 
     match cli.command {
         Commands::Parse {
             banks,
             currency_type,
-            sort,
-        } => handle_parse(banks, currency_type, sort).await?,
+            table,
+            max_age,
+            exclude_stale,
+            format,
+            output,
+        } => {
+            handle_parse(
+                banks,
+                currency_type,
+                table,
+                max_age,
+                exclude_stale,
+                format,
+                output,
+            )
+            .await?
+        }
+        Commands::Watch {
+            banks,
+            currency_type,
+            table,
+            interval,
+        } => watch::handle_watch(banks, currency_type, table, interval).await?,
+        Commands::Tui { banks } => tui::handle_tui(banks).await?,
+        Commands::Best {
+            currency_type,
+            banks,
+            currencies,
+            top,
+            format,
+        } => best::handle_best(banks, currency_type, currencies, top, format).await?,
+        Commands::Convert {
+            amount,
+            currency,
+            banks,
+            side,
+            currency_type,
+            format,
+        } => convert::handle_convert(amount, currency, banks, side, currency_type, format).await?,
+        Commands::Info { bank, format } => info::handle_info(bank, format).await?,
+        Commands::Metals {
+            banks,
+            metals,
+            format,
+        } => metals::handle_metals(banks, metals, format).await?,
+        Commands::Branches {
+            banks,
+            best,
+            top,
+            format,
+        } => branches::handle_branches(banks, best, top, format).await?,
+        Commands::Diff {
+            old,
+            new,
+            changed,
+            format,
+        } => diff::handle_diff(old, new, changed, format).await?,
+        Commands::Chart {
+            dir,
+            banks,
+            currency_type,
+            currencies,
+            since,
+            style,
+            height,
+        } => chart::handle_chart(dir, banks, currency_type, currencies, since, style, height)?,
+        Commands::Export { banks, xlsx } => export::handle_export(banks, xlsx).await?,
+        Commands::Stream {
+            currency_type,
+            banks,
+            currencies,
+            format,
+        } => stream::handle_stream(banks, currency_type, currencies, format).await?,
         Commands::Json { banks } => handle_json(banks).await?,
+        Commands::Cache { command } => cache::handle_cache(command)?,
+        Commands::Completions { shell } => generate::handle_completions(shell),
+        Commands::Man => generate::handle_man()?,
     };
 
-    if cli.time {
+    if print_time {
         println!("Time elapsed: {:?}", start.elapsed());
     }
     std::process::exit(0);
@@ -93,42 +544,77 @@ impl Colorized for &str {
     }
 }
 
+/// Shown in place of a rate the bank does not quote.
+pub(crate) const MISSING: &str = "—";
+
+pub(crate) fn format_rate(rate: &Option<f64>) -> String {
+    match rate {
+        Some(rate) => i18n::format_number(*rate),
+        None => MISSING.to_string(),
+    }
+}
+
 impl Colorized for Currency {
     fn colorized(&self) -> String {
-        format!(
-            "{} / {}",
-            self.buy().unwrap_or_default().to_string().bright_green(),
-            self.sell().unwrap_or_default().to_string().bright_red()
-        )
+        let (buy, sell) = (format_rate(self.buy()), format_rate(self.sell()));
+
+        match self.validation().verdict() {
+            Verdict::Valid => format!("{} / {}", buy.bright_green(), sell.bright_red()),
+            Verdict::Suspicious => format!(
+                "{} / {} {}",
+                buy.bright_green().underline(),
+                sell.bright_red().underline(),
+                "?".bright_yellow().bold()
+            ),
+            Verdict::Rejected => format!(
+                "{} / {} {}",
+                buy.dimmed().strikethrough(),
+                sell.dimmed().strikethrough(),
+                "!".bright_magenta().bold()
+            ),
+        }
     }
 }
 
 async fn handle_parse(
     banks: Vec<Banks>,
     currency_type: CurrencyType,
-    sort_by: Option<CurrencyName>,
+    table_args: TableArgs,
+    max_age: Option<Duration>,
+    exclude_stale: bool,
+    format: OutputFormat,
+    output: Option<PathBuf>,
 ) -> Result<()> {
-    match banks.is_empty() || banks.contains(&Banks::All) {
-        true => {
-            let banks = armbankrate_parser::parse_all()
-                .await
-                .with_context(|| ERR_MSG)?;
+    let banks = fetch_banks(&banks).await?;
+    let rendered = match format {
+        OutputFormat::Table => TableBuilder::new(banks, currency_type, table_args)
+            .with_max_age(max_age, exclude_stale)
+            .build(),
+        OutputFormat::Markdown => Report::new(banks, currency_type, table_args)
+            .with_max_age(max_age, exclude_stale)
+            .markdown(),
+        OutputFormat::Html => Report::new(banks, currency_type, table_args)
+            .with_max_age(max_age, exclude_stale)
+            .html(),
+    };
 
-            let table = TableBuilder::new(banks, currency_type, sort_by).build();
-            println!("{}", table);
-        }
-        false => {
-            let banks = armbankrate_parser::parse(&banks)
-                .await
-                .with_context(|| ERR_MSG)?;
-            let table = TableBuilder::new(banks, currency_type, sort_by).build();
-            println!("{}", table);
-        }
+    match output {
+        Some(output) => std::fs::write(&output, rendered)
+            .with_context(|| format!("Failed to write {}", output.display()))?,
+        None => println!("{}", rendered),
     }
 
     Ok(())
 }
 
+pub(crate) async fn fetch_banks(banks: &[Banks]) -> Result<Vec<Bank>> {
+    match banks.is_empty() || banks.contains(&Banks::All) {
+        true => armbankrate_parser::parse_all().await,
+        false => armbankrate_parser::parse(banks).await,
+    }
+    .with_context(|| ERR_MSG)
+}
+
 async fn handle_json(banks: Vec<Banks>) -> Result<()> {
     match banks.is_empty() {
         true => {
@@ -148,61 +634,168 @@ async fn handle_json(banks: Vec<Banks>) -> Result<()> {
     Ok(())
 }
 
-#[derive(ArgEnum, Display, Debug, Clone)]
-enum CurrencyName {
-    UsdBuy,
-    UsdSell,
-    GbpBuy,
-    GbpSell,
-    EurBuy,
-    EurSell,
-    RubBuy,
-    RubSell,
-}
-
-impl CurrencyName {
-    fn to_sort_data(&self, currency_type: armbankrate_parser::CurrencyType) -> SortData {
-        match self {
-            CurrencyName::UsdBuy => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::USD,
-                OrderType::Buy,
-            ),
-            CurrencyName::UsdSell => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::USD,
-                OrderType::Sell,
-            ),
-            CurrencyName::GbpBuy => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::GBP,
-                OrderType::Buy,
-            ),
-            CurrencyName::GbpSell => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::GBP,
-                OrderType::Sell,
-            ),
-            CurrencyName::EurBuy => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::EUR,
-                OrderType::Buy,
-            ),
-            CurrencyName::EurSell => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::EUR,
-                OrderType::Sell,
-            ),
-            CurrencyName::RubBuy => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::RUB,
-                OrderType::Buy,
-            ),
-            CurrencyName::RubSell => SortData::new(
-                currency_type,
-                armbankrate_parser::CurrencyName::RUB,
-                OrderType::Sell,
-            ),
+/// Sort key in the form `<currency>-<field>[:<direction>]`, e.g. `usd-buy`, `eur-spread:asc`.
+///
+/// Fields are `buy`, `sell`, `spread`, `buy-deviation` and `sell-deviation`,
+/// directions are `asc`, `desc` (default) and `best` (best rate for the customer first).
+#[derive(Debug, Clone)]
+pub(crate) struct SortKey {
+    currency_name: armbankrate_parser::CurrencyName,
+    order_type: OrderType,
+    direction: Option<Direction>,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || {
+            format!(
+                "invalid sort key `{value}`, expected <currency>-<field>[:asc|desc|best] \
+                 (fields: buy, sell, spread, buy-deviation, sell-deviation)"
+            )
+        };
+
+        let (key, direction) = match value.split_once(':') {
+            Some((key, direction)) => (key, Some(direction)),
+            None => (value, None),
+        };
+        let (currency_name, field) = key.split_once('-').ok_or_else(error)?;
+
+        let currency_name =
+            armbankrate_parser::CurrencyName::from_str(currency_name).map_err(|_| error())?;
+        let order_type = match field.to_lowercase().as_str() {
+            "buy" => OrderType::Buy,
+            "sell" => OrderType::Sell,
+            "spread" => OrderType::Spread,
+            "buy-deviation" => OrderType::Deviation(Side::Buy),
+            "sell-deviation" => OrderType::Deviation(Side::Sell),
+            _ => return Err(error()),
+        };
+        let direction = match direction
+            .map(|direction| direction.to_lowercase())
+            .as_deref()
+        {
+            None | Some("desc") => Some(Direction::Descending),
+            Some("asc") => Some(Direction::Ascending),
+            Some("best") => None,
+            Some(_) => return Err(error()),
+        };
+
+        Ok(Self {
+            currency_name,
+            order_type,
+            direction,
+        })
+    }
+}
+
+impl SortKey {
+    pub(crate) fn to_sort_data(
+        &self,
+        currency_type: armbankrate_parser::CurrencyType,
+        missing: Missing,
+    ) -> SortData {
+        let currency_name = self.currency_name.clone();
+
+        match self.direction {
+            Some(direction) => SortData::new(currency_type, currency_name, self.order_type)
+                .with_direction(direction),
+            None => SortData::best_for_customer(currency_type, currency_name, self.order_type),
+        }
+        .with_missing(missing)
+    }
+}
+
+#[derive(ArgEnum, Display, Debug, Clone, Copy)]
+enum MissingPlacement {
+    First,
+    Last,
+}
+
+impl From<MissingPlacement> for Missing {
+    fn from(placement: MissingPlacement) -> Self {
+        match placement {
+            MissingPlacement::First => Missing::First,
+            MissingPlacement::Last => Missing::Last,
+        }
+    }
+}
+
+/// Parses an amount of a currency, which has to be a finite positive number.
+fn parse_amount(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount > 0.0 => Ok(amount),
+        _ => Err(format!(
+            "invalid amount `{value}`, expected a positive number (e.g. 500)"
+        )),
+    }
+}
+
+/// Parses a number of entries to show, which has to be at least 1.
+fn parse_count(value: &str) -> Result<usize, String> {
+    match value.trim().parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!(
+            "invalid count `{value}`, expected a positive whole number"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_keys_default_to_descending() {
+        let key = SortKey::from_str("usd-buy").unwrap();
+
+        assert_eq!(key.currency_name, armbankrate_parser::CurrencyName::USD);
+        assert_eq!(key.order_type, OrderType::Buy);
+        assert_eq!(key.direction, Some(Direction::Descending));
+    }
+
+    #[test]
+    fn sort_keys_take_fields_and_directions() {
+        let key = SortKey::from_str("EUR-Spread:ASC").unwrap();
+        assert_eq!(key.order_type, OrderType::Spread);
+        assert_eq!(key.direction, Some(Direction::Ascending));
+
+        let key = SortKey::from_str("rub-sell-deviation:desc").unwrap();
+        assert_eq!(key.currency_name, armbankrate_parser::CurrencyName::RUB);
+        assert_eq!(key.order_type, OrderType::Deviation(Side::Sell));
+        assert_eq!(key.direction, Some(Direction::Descending));
+
+        // `best` leaves the direction to the order type
+        let key = SortKey::from_str("gbp-buy-deviation:best").unwrap();
+        assert_eq!(key.order_type, OrderType::Deviation(Side::Buy));
+        assert_eq!(key.direction, None);
+    }
+
+    #[test]
+    fn invalid_sort_keys_are_rejected() {
+        for value in ["usd", "usd-", "xyz-buy", "usd-median", "usd-buy:up", "-buy"] {
+            assert!(SortKey::from_str(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn amounts_must_be_finite_and_positive() {
+        assert_eq!(parse_amount("500"), Ok(500.0));
+        assert_eq!(parse_amount("0.5"), Ok(0.5));
+
+        for value in ["0", "-1", "NaN", "inf", "-inf", "1e400", "abc", ""] {
+            assert!(parse_amount(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn counts_must_be_positive() {
+        assert_eq!(parse_count("1"), Ok(1));
+        assert_eq!(parse_count("10"), Ok(10));
+
+        for value in ["0", "-1", "1.5", "abc", ""] {
+            assert!(parse_count(value).is_err(), "{value}");
         }
     }
 }
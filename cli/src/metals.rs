@@ -0,0 +1,103 @@
+use crate::{fetch_banks, format_rate, i18n, Banks, Colorized, ERR_MSG, MISSING};
+use anyhow::{Context, Result};
+use armbankrate_parser::metals::{metals_json, Metal};
+use armbankrate_parser::{Bank, BankImpl};
+use clap::ArgEnum;
+use colored::Colorize;
+use std::fmt::Display;
+use tabled::builder::Builder;
+use tabled::object::Segment;
+use tabled::{Alignment, Header, Modify, Style};
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum MetalsFormat {
+    Table,
+    Json,
+}
+
+pub(crate) async fn handle_metals(
+    banks: Vec<Banks>,
+    metals: Vec<Metal>,
+    format: MetalsFormat,
+) -> Result<()> {
+    let banks = fetch_banks(&banks).await?;
+
+    match format {
+        MetalsFormat::Json => {
+            let json = metals_json(&banks).with_context(|| ERR_MSG)?;
+            println!("{json}");
+        }
+        MetalsFormat::Table => match build_table(&banks, &metals) {
+            Some(table) => println!("{table}"),
+            None => eprintln!("{}", i18n::tr("message.no_metals")),
+        },
+    }
+
+    Ok(())
+}
+
+/// Quotes of the given metals, all of them when none are given, `None` when there are no quotes.
+pub(crate) fn build_table(banks: &[Bank], metals: &[Metal]) -> Option<String> {
+    let mut builder = Builder::default();
+    builder.set_columns(
+        i18n::columns([
+            "bank",
+            "metal",
+            "weight",
+            "purity",
+            "buy",
+            "sell",
+            "buy_per_gram",
+            "sell_per_gram",
+        ])
+        .map(|column| column.as_str().colorized()),
+    );
+
+    let mut rows = 0;
+    for bank in banks {
+        for quote in bank.metals().quotes() {
+            if !metals.is_empty() && !metals.contains(quote.metal()) {
+                continue;
+            }
+
+            builder.add_record([
+                i18n::bank_name(bank.get_name())
+                    .bright_yellow()
+                    .bold()
+                    .to_string(),
+                i18n::tr(&format!("metal.{:?}", quote.metal()).to_lowercase()),
+                format!("{} {}", quote.weight(), quote.unit().symbol()),
+                quote
+                    .purity()
+                    .map_or_else(|| MISSING.to_string(), |purity| purity.to_string()),
+                format_rate(quote.buy()).bright_green().to_string(),
+                format_rate(quote.sell()).bright_red().to_string(),
+                format_rate(&quote.buy_per_gram().map(round))
+                    .bright_green()
+                    .to_string(),
+                format_rate(&quote.sell_per_gram().map(round))
+                    .bright_red()
+                    .to_string(),
+            ]);
+            rows += 1;
+        }
+    }
+
+    if rows == 0 {
+        return None;
+    }
+
+    let table = builder
+        .build()
+        .with(Header(
+            i18n::tr("title.metals").bold().bright_green().to_string(),
+        ))
+        .with(Modify::new(Segment::all()).with(Alignment::center()))
+        .with(Style::extended());
+    Some(table.to_string())
+}
+
+/// Rounds a per gram price to two decimals.
+fn round(price: f64) -> f64 {
+    (price * 100.0).round() / 100.0
+}
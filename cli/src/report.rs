@@ -0,0 +1,450 @@
+use crate::i18n::{self, tr, tr_with};
+use crate::{CurrencyType, TableArgs, MISSING};
+use armbankrate_parser::best::best_rates;
+use armbankrate_parser::layout::{GridCell, Header, Layout, RateGrid};
+use armbankrate_parser::validation::Side;
+use armbankrate_parser::{Bank, BankImpl, Currency, CurrencyBody};
+use clap::ArgEnum;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::time::Duration;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border:1px solid #ccc;padding:.4em .8em;text-align:center}\
+th{background:#eef2f8}.buy{color:#1a7f37}.sell{color:#c62828}\
+.generated{color:#666}.stale{color:#8e24aa}";
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum OutputFormat {
+    Table,
+    Markdown,
+    Html,
+}
+
+type BestKey = (armbankrate_parser::CurrencyType, String, Side, String);
+
+/// Renders the rates of the parse command as Markdown or a self-contained HTML page.
+pub(crate) struct Report {
+    banks: Vec<Bank>,
+    currency_type: CurrencyType,
+    args: TableArgs,
+    max_age: Option<Duration>,
+    exclude_stale: bool,
+    best: HashSet<BestKey>,
+}
+
+impl Report {
+    pub(crate) fn new(mut banks: Vec<Bank>, currency_type: CurrencyType, args: TableArgs) -> Self {
+        args.retain_banks(&mut banks);
+
+        Self {
+            banks,
+            currency_type,
+            args,
+            max_age: None,
+            exclude_stale: false,
+            best: HashSet::new(),
+        }
+    }
+
+    /// Flags rates older than `max_age`, or leaves them out when `exclude_stale` is set.
+    pub(crate) fn with_max_age(mut self, max_age: Option<Duration>, exclude_stale: bool) -> Self {
+        self.max_age = max_age;
+        self.exclude_stale = exclude_stale;
+        self
+    }
+
+    fn is_stale(&self, currencies: &CurrencyBody) -> bool {
+        self.max_age
+            .is_some_and(|max_age| currencies.is_stale(max_age))
+    }
+
+    fn is_shown(&self, bank: &Bank, currency_type: &armbankrate_parser::CurrencyType) -> bool {
+        bank.has_currencies(currency_type)
+            && !(self.exclude_stale && self.is_stale(bank.currencies(currency_type)))
+    }
+
+    /// Banks with the best rate of every currency and side among the shown ones.
+    fn best(&self) -> HashSet<BestKey> {
+        let mut best = HashSet::new();
+        for best_rates in best_rates(&self.banks, usize::MAX) {
+            let shown = |name: &String| {
+                self.banks.iter().any(|bank| {
+                    bank.get_name() == name && self.is_shown(bank, best_rates.currency_type())
+                })
+            };
+            let banks = best_rates
+                .entries()
+                .iter()
+                .map(|entry| {
+                    entry
+                        .banks()
+                        .iter()
+                        .filter(|bank| shown(bank))
+                        .collect::<Vec<_>>()
+                })
+                .find(|banks| !banks.is_empty())
+                .unwrap_or_default();
+
+            for bank in banks {
+                best.insert((
+                    best_rates.currency_type().clone(),
+                    format!("{:?}", best_rates.currency_name()),
+                    best_rates.side(),
+                    bank.clone(),
+                ));
+            }
+        }
+
+        best
+    }
+
+    /// Age note of a bank header whose rates are older than `--max-age`.
+    fn stale_note(
+        &self,
+        header: &Header,
+        currency_type: &armbankrate_parser::CurrencyType,
+    ) -> Option<String> {
+        let currencies = match header {
+            Header::Bank(bank) => bank.currencies(currency_type),
+            Header::Currency(_) => return None,
+        };
+        if !self.is_stale(currencies) {
+            return None;
+        }
+
+        let age = currencies.age().unwrap_or_default();
+        let age = humantime::format_duration(Duration::from_secs(age.as_secs()));
+        Some(tr_with("stale", age))
+    }
+
+    fn is_best(
+        &self,
+        cell: &GridCell,
+        currency_type: &armbankrate_parser::CurrencyType,
+        side: Side,
+    ) -> bool {
+        self.best.contains(&(
+            currency_type.clone(),
+            format!("{:?}", cell.currency_name()),
+            side,
+            cell.bank().get_name().to_string(),
+        ))
+    }
+
+    /// Renders each rate type as a section, `render` receives the title and the grid.
+    fn sections<F>(&mut self, mut render: F) -> Vec<String>
+    where
+        F: FnMut(&Self, &str, &RateGrid, &armbankrate_parser::CurrencyType) -> String,
+    {
+        let currency_names = self.args.currency_names();
+        let mut sections = Vec::new();
+        self.best = self.best();
+
+        for currency_type in self.currency_type.shown_rate_types(&self.banks) {
+            self.args.sort_banks(&mut self.banks, currency_type.clone());
+
+            let banks: Vec<&Bank> = self
+                .banks
+                .iter()
+                .filter(|bank| self.is_shown(bank, &currency_type))
+                .collect();
+            let grid = RateGrid::new(&banks, &currency_type, &currency_names, self.args.layout());
+            let title = tr(&format!("report.{}", currency_type.key()));
+
+            sections.push(render(self, &title, &grid, &currency_type));
+        }
+
+        sections
+    }
+
+    pub(crate) fn markdown(&mut self) -> String {
+        let mut output = format!(
+            "# {}\n\n{}\n",
+            tr("report.title"),
+            tr_with("report.generated", generated_at())
+        );
+
+        for section in self.sections(|report, title, grid, currency_type| {
+            let mut section = format!("\n## {title}\n\n");
+
+            let mut header = vec![corner(grid)];
+            header.extend(
+                grid.columns()
+                    .iter()
+                    .map(|header| report.markdown_header(header, currency_type)),
+            );
+            section.push_str(&format!("| {} |\n", header.join(" | ")));
+            section.push_str(&format!("|{}\n", "---|".repeat(header.len())));
+
+            for row in grid.rows() {
+                let mut cells = vec![report.markdown_header(row.header(), currency_type)];
+                cells.extend(row.cells().iter().map(|cell| {
+                    let [buy, sell] =
+                        report
+                            .rates(cell, currency_type)
+                            .map(|(rate, best)| match (rate, best) {
+                                (Some(rate), true) => {
+                                    format!("**{}**", i18n::format_number(rate))
+                                }
+                                (Some(rate), false) => i18n::format_number(rate),
+                                (None, _) => MISSING.to_string(),
+                            });
+                    format!("{buy} / {sell}")
+                }));
+                section.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+
+            section
+        }) {
+            output.push_str(&section);
+        }
+
+        output.push_str(&format!("\n## {}\n\n", tr("report.sources")));
+        for bank in &self.banks {
+            output.push_str(&format!(
+                "- [{}]({})\n",
+                escape_markdown(&i18n::bank_name(bank.get_name())),
+                bank.get_url()
+            ));
+        }
+
+        output
+    }
+
+    pub(crate) fn html(&mut self) -> String {
+        let title = escape_html(&tr("report.title"));
+        let mut body = format!(
+            "<h1>{title}</h1>\n<p class=\"generated\">{}</p>\n",
+            escape_html(&tr_with("report.generated", generated_at()))
+        );
+
+        for section in self.sections(|report, title, grid, currency_type| {
+            let mut section = format!("<h2>{}</h2>\n<table>\n<thead><tr>", escape_html(title));
+
+            section.push_str(&format!("<th>{}</th>", escape_html(&corner(grid))));
+            for header in grid.columns() {
+                section.push_str(&format!(
+                    "<th>{}</th>",
+                    report.html_header(header, currency_type)
+                ));
+            }
+            section.push_str("</tr></thead>\n<tbody>\n");
+
+            for row in grid.rows() {
+                section.push_str(&format!(
+                    "<tr><th>{}</th>",
+                    report.html_header(row.header(), currency_type)
+                ));
+                for cell in row.cells() {
+                    let [buy, sell] = report
+                        .rates(cell, currency_type)
+                        .map(|(rate, best)| match (rate, best) {
+                            (Some(rate), true) => {
+                                format!("<strong>{}</strong>", i18n::format_number(rate))
+                            }
+                            (Some(rate), false) => i18n::format_number(rate),
+                            (None, _) => MISSING.to_string(),
+                        });
+                    section.push_str(&format!(
+                        "<td><span class=\"buy\">{buy}</span> / <span class=\"sell\">{sell}</span></td>"
+                    ));
+                }
+                section.push_str("</tr>\n");
+            }
+
+            section.push_str("</tbody>\n</table>\n");
+            section
+        }) {
+            body.push_str(&section);
+        }
+
+        body.push_str(&format!(
+            "<h2>{}</h2>\n<ul>\n",
+            escape_html(&tr("report.sources"))
+        ));
+        for bank in &self.banks {
+            body.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                escape_html(bank.get_url()),
+                escape_html(&i18n::bank_name(bank.get_name()))
+            ));
+        }
+        body.push_str("</ul>\n");
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+            escape_html(&i18n::lang())
+        )
+    }
+
+    fn markdown_header(
+        &self,
+        header: &Header,
+        currency_type: &armbankrate_parser::CurrencyType,
+    ) -> String {
+        let text = match header {
+            Header::Bank(bank) => format!(
+                "[{}]({})",
+                escape_markdown(&i18n::bank_name(bank.get_name())),
+                bank.get_url()
+            ),
+            Header::Currency(currency_name) => escape_markdown(&i18n::currency_name(currency_name)),
+        };
+
+        match self.stale_note(header, currency_type) {
+            Some(note) => format!("{text} _({})_", escape_markdown(&note)),
+            None => text,
+        }
+    }
+
+    fn html_header(
+        &self,
+        header: &Header,
+        currency_type: &armbankrate_parser::CurrencyType,
+    ) -> String {
+        let text = match header {
+            Header::Bank(bank) => format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(bank.get_url()),
+                escape_html(&i18n::bank_name(bank.get_name()))
+            ),
+            Header::Currency(currency_name) => escape_html(&i18n::currency_name(currency_name)),
+        };
+
+        match self.stale_note(header, currency_type) {
+            Some(note) => format!(
+                "{text}<br><small class=\"stale\">{}</small>",
+                escape_html(&note)
+            ),
+            None => text,
+        }
+    }
+
+    /// Buy and sell rates of a cell, each flagged when it is the best one.
+    fn rates(
+        &self,
+        cell: &GridCell,
+        currency_type: &armbankrate_parser::CurrencyType,
+    ) -> [(Option<f64>, bool); 2] {
+        let currency: &Currency = cell.currency();
+
+        [
+            (
+                *currency.buy(),
+                self.is_best(cell, currency_type, Side::Buy),
+            ),
+            (
+                *currency.sell(),
+                self.is_best(cell, currency_type, Side::Sell),
+            ),
+        ]
+    }
+}
+
+fn corner(grid: &RateGrid) -> String {
+    match grid.layout() {
+        Layout::BankRows => tr("header.bank"),
+        Layout::CurrencyRows => tr("header.currency"),
+    }
+}
+
+fn generated_at() -> String {
+    chrono::Local::now()
+        .format("%Y-%m-%d %H:%M:%S %:z")
+        .to_string()
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MissingPlacement, TableLayout, TableStyle};
+    use armbankrate_parser::CurrencyName;
+    use chrono::Utc;
+    use std::str::FromStr;
+
+    fn args() -> TableArgs {
+        TableArgs {
+            sort: Vec::new(),
+            missing: MissingPlacement::Last,
+            currencies: vec![CurrencyName::USD],
+            exclude_banks: Vec::new(),
+            layout: TableLayout::Banks,
+            style: TableStyle::Extended,
+            amount: None,
+        }
+    }
+
+    /// Cash-only report of two banks, the better Idbank rates fetched two hours ago.
+    fn report() -> Report {
+        let banks = [("unibank", 385.0, 0), ("idbank", 387.0, 2)]
+            .into_iter()
+            .map(|(name, buy, hours)| {
+                let mut bank = Bank::from_str(name).unwrap();
+                let currencies = bank.cash_currencies_mut();
+                currencies.fill_from_currency(Currency::new(
+                    CurrencyName::USD,
+                    Some(buy),
+                    Some(buy + 5.0),
+                ));
+                currencies.set_fetched_at(Utc::now() - chrono::Duration::hours(hours));
+                bank
+            })
+            .collect();
+
+        Report::new(banks, CurrencyType::Cash, args())
+    }
+
+    #[test]
+    fn stale_rates_are_flagged() {
+        let markdown = report()
+            .with_max_age(Some(Duration::from_secs(3600)), false)
+            .markdown();
+
+        let idbank = markdown
+            .lines()
+            .find(|line| line.contains("Idbank"))
+            .unwrap();
+        assert!(idbank.contains(&tr_with("stale", "2h")));
+        assert!(idbank.contains("**387** / 392"));
+        let unibank = markdown
+            .lines()
+            .find(|line| line.contains("Unibank"))
+            .unwrap();
+        assert!(!unibank.contains(&tr_with("stale", "")));
+    }
+
+    #[test]
+    fn stale_rates_are_left_out() {
+        let markdown = report()
+            .with_max_age(Some(Duration::from_secs(3600)), true)
+            .markdown();
+
+        let rows: Vec<&str> = markdown
+            .lines()
+            .filter(|line| line.starts_with("| [Idbank]"))
+            .collect();
+        assert!(rows.is_empty());
+        // The best rate is picked among the listed banks
+        let unibank = markdown
+            .lines()
+            .find(|line| line.starts_with("| [Unibank]"))
+            .unwrap();
+        assert!(unibank.contains("**385** / **390**"));
+    }
+}
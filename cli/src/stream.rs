@@ -0,0 +1,109 @@
+use crate::i18n::{self, tr};
+use crate::{Banks, Colorized, CurrencyType, ERR_MSG};
+use anyhow::{Context, Result};
+use armbankrate_parser::stream::{parse_all_stream, parse_stream};
+use armbankrate_parser::{BankImpl, CurrencyName};
+use clap::ArgEnum;
+use colored::Colorize;
+use futures::StreamExt;
+use std::fmt::Display;
+use std::time::Instant;
+
+#[derive(ArgEnum, Display, Debug, Clone)]
+pub(crate) enum StreamFormat {
+    Rows,
+    Ndjson,
+}
+
+/// Prints every bank as soon as it is parsed, then a summary of failures and timing on stderr.
+pub(crate) async fn handle_stream(
+    banks: Vec<Banks>,
+    currency_type: CurrencyType,
+    currencies: Vec<CurrencyName>,
+    format: StreamFormat,
+) -> Result<()> {
+    let start = Instant::now();
+    let mut outcomes = match banks.is_empty() || banks.contains(&Banks::All) {
+        true => parse_all_stream(),
+        false => parse_stream(&banks).with_context(|| ERR_MSG)?,
+    };
+
+    let currencies = match currencies.is_empty() {
+        true => vec![
+            CurrencyName::USD,
+            CurrencyName::EUR,
+            CurrencyName::RUB,
+            CurrencyName::GBP,
+        ],
+        false => currencies,
+    };
+    let currency_types = currency_type.rate_types();
+
+    let mut total = 0;
+    let mut failures = Vec::new();
+    while let Some(outcome) = outcomes.next().await {
+        total += 1;
+        let bank = outcome.bank();
+
+        match format {
+            StreamFormat::Ndjson => println!("{}", outcome.to_json().with_context(|| ERR_MSG)?),
+            StreamFormat::Rows => {
+                let mut row = format!(
+                    "{:<14} {:>7}",
+                    i18n::bank_name(bank.get_name()).bright_yellow().bold(),
+                    format!("{}ms", outcome.elapsed().as_millis()).dimmed()
+                );
+
+                match outcome.error() {
+                    Some(error) => row.push_str(&format!("  {}", error.to_string().bright_red())),
+                    None => {
+                        for currency_type in currency_types
+                            .iter()
+                            .filter(|currency_type| bank.has_currencies(currency_type))
+                        {
+                            let header = tr(&format!("header.{}", currency_type.key()));
+                            row.push_str(&format!("  {}", header.bright_green().bold()));
+
+                            for currency_name in &currencies {
+                                let currency =
+                                    bank.currencies(currency_type).get_rate(currency_name);
+                                row.push_str(&format!(
+                                    " {} {}",
+                                    i18n::currency_name(currency_name).as_str().colorized(),
+                                    currency.colorized()
+                                ));
+                            }
+                        }
+                    }
+                }
+
+                println!("{row}");
+            }
+        }
+
+        if let Some(error) = outcome.error() {
+            failures.push(format!("{} ({error})", bank.get_name()));
+        }
+    }
+
+    eprintln!(
+        "{}",
+        i18n::tr_args(
+            "stream.summary",
+            &[
+                &(total - failures.len()),
+                &total,
+                &format!("{:?}", start.elapsed())
+            ]
+        )
+    );
+    if !failures.is_empty() {
+        eprintln!(
+            "{}: {}",
+            tr("stream.failed").bright_red().bold(),
+            failures.join(", ")
+        );
+    }
+
+    Ok(())
+}
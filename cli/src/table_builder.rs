@@ -1,120 +1,191 @@
-use crate::{Colorized, CurrencyName, CurrencyType};
-use armbankrate_parser::{Bank, BankImpl, CurrencyBody};
+use crate::i18n::{self, tr};
+use crate::watch::{delta, Snapshot};
+use crate::{Colorized, CurrencyType, TableArgs, TableStyle};
+use armbankrate_parser::layout::{GridCell, Header, Layout, RateGrid};
+use armbankrate_parser::{Bank, BankImpl, CurrencyBody, CurrencyName};
 use colored::Colorize;
 use std::cell::RefCell;
+use std::time::Duration;
 
 use tabled::builder::Builder;
 use tabled::object::Segment;
-use tabled::{Alignment, Concat, Header, Modify, Style, Table};
+use tabled::{Alignment, Concat, Modify, Style, Table};
 
 pub(crate) struct TableBuilder {
     banks: RefCell<Vec<Bank>>,
     currency_type: CurrencyType,
-    sort: Option<CurrencyName>,
+    args: TableArgs,
+    currency_names: Vec<CurrencyName>,
+    max_age: Option<Duration>,
+    exclude_stale: bool,
+    previous: Option<Snapshot>,
     builder: RefCell<Builder>,
 }
 
 impl TableBuilder {
-    pub(crate) fn new(
-        banks: Vec<Bank>,
-        currency_type: CurrencyType,
-        sort: Option<CurrencyName>,
-    ) -> Self {
+    pub(crate) fn new(mut banks: Vec<Bank>, currency_type: CurrencyType, args: TableArgs) -> Self {
+        args.retain_banks(&mut banks);
+
         Self {
             banks: RefCell::new(banks),
             currency_type,
-            sort,
+            currency_names: args.currency_names(),
+            args,
+            max_age: None,
+            exclude_stale: false,
+            previous: None,
             builder: RefCell::new(Builder::default()),
         }
     }
 
+    /// Flags rates older than `max_age`, or leaves them out when `exclude_stale` is set.
+    pub(crate) fn with_max_age(mut self, max_age: Option<Duration>, exclude_stale: bool) -> Self {
+        self.max_age = max_age;
+        self.exclude_stale = exclude_stale;
+        self
+    }
+
+    /// Highlights rates that changed since the `previous` snapshot.
+    pub(crate) fn with_previous(mut self, previous: Snapshot) -> Self {
+        self.previous = Some(previous);
+        self
+    }
+
     pub(crate) fn build(&self) -> String {
-        match self.currency_type {
-            CurrencyType::All => self
-                .build_cash()
-                .with(Style::ascii())
-                .with(Concat::vertical(self.build_no_cash()))
-                .to_string(),
-            CurrencyType::Cash => self.build_cash().to_string(),
-            CurrencyType::Noncash => self.build_no_cash().to_string(),
+        let currency_types = self.currency_type.shown_rate_types(&self.banks.borrow());
+        let table = currency_types
+            .into_iter()
+            .map(|currency_type| {
+                self.build_section(currency_type.clone());
+                self.build_table(&currency_type)
+            })
+            .reduce(|table, section| table.with(Concat::vertical(section)))
+            .unwrap_or_else(|| Builder::default().build());
+
+        match self.args.style {
+            TableStyle::Extended => table.with(Style::extended()),
+            TableStyle::Ascii => table.with(Style::ascii()),
+            TableStyle::Markdown => table.with(Style::markdown()),
+            TableStyle::Unicode => table.with(Style::modern()),
+            TableStyle::Compact => table.with(Style::blank()),
         }
+        .to_string()
     }
 
-    fn build_table(&self, currency_header: CurrencyHeader) -> Table {
+    fn build_table(&self, currency_type: &armbankrate_parser::CurrencyType) -> Table {
         // Reset builder when building
         let builder = self.builder.take();
 
-        let header = match currency_header {
-            CurrencyHeader::Cash => "CASH".bold().bright_green().to_string(),
-            CurrencyHeader::Noncash => "NON-CASH".bold().bright_green().to_string(),
-        };
+        let header = tr(&format!("header.{}", currency_type.key()))
+            .bold()
+            .bright_green()
+            .to_string();
 
-        builder
-            .build()
-            .with(Header(header))
-            .with(
-                Modify::new(Segment::all())
-                    .with(Alignment::center())
-                    .with(Alignment::center()),
-            )
-            .with(Style::extended())
+        builder.build().with(tabled::Header(header)).with(
+            Modify::new(Segment::all())
+                .with(Alignment::center())
+                .with(Alignment::center()),
+        )
     }
 
-    fn build_cash(&self) -> Table {
-        self.prepare_columns();
-
-        self.sort_banks(armbankrate_parser::CurrencyType::Cash);
-
-        for bank in self.banks.borrow().iter() {
-            let currencies: &CurrencyBody = bank.cash_currencies();
-            self.add_column(bank, currencies);
+    fn build_section(&self, currency_type: armbankrate_parser::CurrencyType) {
+        self.args
+            .sort_banks(&mut self.banks.borrow_mut(), currency_type.clone());
+
+        let banks = self.banks.borrow();
+        let banks: Vec<&Bank> = banks
+            .iter()
+            .filter(|bank| bank.has_currencies(&currency_type))
+            .filter(|bank| !(self.exclude_stale && self.is_stale(bank.currencies(&currency_type))))
+            .collect();
+        let grid = RateGrid::new(
+            &banks,
+            &currency_type,
+            &self.currency_names,
+            self.args.layout(),
+        );
+
+        let corner = match grid.layout() {
+            Layout::BankRows => tr("header.bank"),
+            Layout::CurrencyRows => tr("header.currency"),
+        };
+        let mut columns = vec![corner.as_str().colorized()];
+        columns.extend(
+            grid.columns()
+                .iter()
+                .map(|header| self.header_cell(header, &currency_type)),
+        );
+        self.builder.borrow_mut().set_columns(columns);
+
+        for row in grid.rows() {
+            let mut record = vec![self.header_cell(row.header(), &currency_type)];
+            record.extend(
+                row.cells()
+                    .iter()
+                    .map(|cell| self.currency_cell(cell, &currency_type)),
+            );
+            self.builder.borrow_mut().add_record(record);
         }
-
-        self.build_table(CurrencyHeader::Cash)
     }
 
-    fn build_no_cash(&self) -> Table {
-        self.prepare_columns();
+    fn is_stale(&self, currencies: &CurrencyBody) -> bool {
+        self.max_age
+            .is_some_and(|max_age| currencies.is_stale(max_age))
+    }
 
-        self.sort_banks(armbankrate_parser::CurrencyType::Noncash);
+    fn header_cell(
+        &self,
+        header: &Header,
+        currency_type: &armbankrate_parser::CurrencyType,
+    ) -> String {
+        let bank = match header {
+            Header::Bank(bank) => bank,
+            Header::Currency(currency_name) => {
+                return i18n::currency_name(currency_name).as_str().colorized()
+            }
+        };
 
-        for bank in self.banks.borrow().iter() {
-            let currencies: &CurrencyBody = bank.no_cash_currencies();
-            self.add_column(bank, currencies);
+        let name = i18n::bank_name(bank.get_name())
+            .bright_yellow()
+            .bold()
+            .to_string();
+        let currencies = bank.currencies(currency_type);
+        if !self.is_stale(currencies) {
+            return name;
         }
 
-        self.build_table(CurrencyHeader::Noncash)
+        let age = currencies.age().unwrap_or_default();
+        let age = humantime::format_duration(Duration::from_secs(age.as_secs()));
+        format!("{name}\n{}", i18n::tr_with("stale", age).bright_magenta())
     }
 
-    fn add_column(&self, bank: &Bank, currencies: &CurrencyBody) {
-        self.builder.borrow_mut().add_record([
-            &bank.get_name().bright_yellow().bold().to_string(),
-            &currencies.get_usd_rate().colorized(),
-            &currencies.get_eur_rate().colorized(),
-            &currencies.get_rub_rate().colorized(),
-            &currencies.get_gbp_rate().colorized(),
-        ]);
-    }
-
-    fn prepare_columns(&self) {
-        self.builder.borrow_mut().set_columns([
-            "Bank".colorized(),
-            "USD".colorized(),
-            "EUR".colorized(),
-            "RUB".colorized(),
-            "GBP".colorized(),
-        ]);
-    }
+    fn currency_cell(
+        &self,
+        cell: &GridCell,
+        currency_type: &armbankrate_parser::CurrencyType,
+    ) -> String {
+        let currency = cell.currency();
+        let previous = self.previous.as_ref().and_then(|previous| {
+            previous.get(cell.bank().get_name(), currency_type, cell.currency_name())
+        });
+        let cell = currency.colorized();
+
+        let (previous_buy, previous_sell) = match previous {
+            Some(previous) => previous,
+            None => return cell,
+        };
 
-    fn sort_banks(&self, currency_type: armbankrate_parser::CurrencyType) {
-        if let Some(sort) = &self.sort {
-            let mut banks = self.banks.borrow_mut();
-            armbankrate_parser::sort::sort_banks(&mut banks, &sort.to_sort_data(currency_type))
+        let buy_delta = delta(*currency.buy(), *previous_buy);
+        let sell_delta = delta(*currency.sell(), *previous_sell);
+        if buy_delta.is_none() && sell_delta.is_none() {
+            return cell;
         }
-    }
-}
 
-enum CurrencyHeader {
-    Cash,
-    Noncash,
+        format!(
+            "{}\n{} / {}",
+            cell,
+            buy_delta.unwrap_or_else(|| "-".to_string()),
+            sell_delta.unwrap_or_else(|| "-".to_string())
+        )
+    }
 }
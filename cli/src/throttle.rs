@@ -0,0 +1,90 @@
+use armbankrate_parser::throttle;
+use clap::Args;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Args, Debug)]
+pub(crate) struct ThrottleArgs {
+    /// Maximum number of requests in flight across all banks
+    #[clap(long, global = true, value_parser)]
+    max_concurrency: Option<usize>,
+
+    /// Minimum time between two requests to the same host (e.g. 500ms, 2s)
+    #[clap(long, global = true, value_parser = humantime::parse_duration)]
+    min_interval: Option<Duration>,
+
+    /// Maximum requests to the same host per period (e.g. 10/1m)
+    #[clap(long, global = true, value_parser)]
+    rate_limit: Option<RateLimit>,
+
+    /// Times a 429/503 response is retried after its Retry-After delay
+    #[clap(long, global = true, value_parser)]
+    max_retries: Option<u32>,
+}
+
+impl ThrottleArgs {
+    pub(crate) fn apply(&self) {
+        let mut limits = throttle::limits();
+
+        if let Some(max_concurrency) = self.max_concurrency {
+            limits = limits.with_max_concurrency(max_concurrency);
+        }
+        if let Some(min_interval) = self.min_interval {
+            limits = limits.with_min_interval(min_interval);
+        }
+        if let Some(rate_limit) = &self.rate_limit {
+            limits = limits.with_rate_limit(rate_limit.requests, rate_limit.period);
+        }
+        if let Some(max_retries) = self.max_retries {
+            let max_retry_after = limits.max_retry_after();
+            limits = limits.with_retries(max_retries, max_retry_after);
+        }
+
+        throttle::set_limits(limits);
+    }
+}
+
+/// Rate limit in the form `<requests>/<period>`.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimit {
+    requests: u32,
+    period: Duration,
+}
+
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error =
+            || format!("invalid rate limit `{value}`, expected <requests>/<period> (e.g. 10/1m)");
+
+        let (requests, period) = value.split_once('/').ok_or_else(error)?;
+        let requests = requests.trim().parse().map_err(|_| error())?;
+        let period = humantime::parse_duration(period.trim()).map_err(|_| error())?;
+
+        Ok(Self { requests, period })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_is_parsed() {
+        let rate_limit = RateLimit::from_str("10/1m").unwrap();
+        assert_eq!(rate_limit.requests, 10);
+        assert_eq!(rate_limit.period, Duration::from_secs(60));
+
+        let rate_limit = RateLimit::from_str(" 3 / 500ms ").unwrap();
+        assert_eq!(rate_limit.requests, 3);
+        assert_eq!(rate_limit.period, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn invalid_rate_limits_are_rejected() {
+        for value in ["10", "10/", "/1m", "ten/1m", "10/soon", "-1/1m"] {
+            assert!(RateLimit::from_str(value).is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,570 @@
+use crate::i18n::{self, tr};
+use crate::{fetch_banks, format_rate, rate_type_name, Banks, MISSING};
+use anyhow::Result;
+use armbankrate_parser::sort::{OrderType, SortData};
+use armbankrate_parser::{Bank, BankImpl, Currency, CurrencyBody, CurrencyName, CurrencyType};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const CURRENCIES: [CurrencyName; 4] = [
+    CurrencyName::USD,
+    CurrencyName::EUR,
+    CurrencyName::RUB,
+    CurrencyName::GBP,
+];
+
+/// How long a wait for a key press lasts before the runtime gets control back.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Sort {
+    column: usize,
+    order_type: OrderType,
+}
+
+struct App {
+    requested: Vec<Banks>,
+    banks: Vec<Bank>,
+    /// Bank names in the order they were fetched, restored when sorting is turned off.
+    order: Vec<String>,
+    currency_type: CurrencyType,
+    visible: [bool; 4],
+    sort: Option<Sort>,
+    disabled: HashSet<String>,
+    show_disabled: bool,
+    table_state: TableState,
+    detail: bool,
+    status: String,
+}
+
+impl App {
+    fn new(requested: Vec<Banks>, banks: Vec<Bank>) -> Self {
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+
+        Self {
+            requested,
+            order: fetch_order(&banks),
+            banks,
+            currency_type: CurrencyType::Cash,
+            visible: [true; 4],
+            sort: None,
+            disabled: HashSet::new(),
+            show_disabled: false,
+            table_state,
+            detail: false,
+            status: String::new(),
+        }
+    }
+
+    fn currencies<'a>(&self, bank: &'a Bank) -> &'a CurrencyBody {
+        bank.currencies(&self.currency_type)
+    }
+
+    /// Rate types reported by at least one of the banks, as tabs.
+    fn rate_types(&self) -> Vec<CurrencyType> {
+        CurrencyType::ALL
+            .into_iter()
+            .filter(|currency_type| {
+                self.banks
+                    .iter()
+                    .any(|bank| bank.has_currencies(currency_type))
+            })
+            .collect()
+    }
+
+    fn visible_currencies(&self) -> Vec<&CurrencyName> {
+        CURRENCIES
+            .iter()
+            .zip(self.visible)
+            .filter_map(|(currency_name, visible)| visible.then_some(currency_name))
+            .collect()
+    }
+
+    /// Banks listed in the table, disabled ones only when they are shown.
+    fn shown_banks(&self) -> Vec<&Bank> {
+        self.banks
+            .iter()
+            .filter(|bank| self.show_disabled || !self.disabled.contains(bank.get_name()))
+            .collect()
+    }
+
+    fn selected_bank(&self) -> Option<&Bank> {
+        self.shown_banks()
+            .get(self.table_state.selected()?)
+            .copied()
+    }
+
+    /// Sorts enabled banks best rate first by the selected column, or in fetch order without
+    /// a sort, disabled ones go last.
+    fn sort_banks(&mut self) {
+        match &self.sort {
+            Some(sort) => {
+                let sort_data = SortData::best_for_customer(
+                    self.currency_type.clone(),
+                    CURRENCIES[sort.column].clone(),
+                    sort.order_type,
+                );
+                armbankrate_parser::sort::sort_banks(&mut self.banks, &sort_data);
+            }
+            None => {
+                let order = &self.order;
+                self.banks
+                    .sort_by_key(|bank| order.iter().position(|name| name == bank.get_name()));
+            }
+        }
+
+        let disabled = &self.disabled;
+        self.banks
+            .sort_by_key(|bank| disabled.contains(bank.get_name()));
+        self.clamp_selection();
+    }
+
+    /// Keeps the selection on a listed bank after banks were hidden.
+    fn clamp_selection(&mut self) {
+        let count = self.shown_banks().len();
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state
+            .select(Some(selected.min(count.saturating_sub(1))));
+    }
+
+    fn move_sort_column(&mut self, forward: bool) {
+        let visible_columns: Vec<usize> = (0..CURRENCIES.len())
+            .filter(|column| self.visible[*column])
+            .collect();
+        if visible_columns.is_empty() {
+            return;
+        }
+
+        let position = self.sort.as_ref().and_then(|sort| {
+            visible_columns
+                .iter()
+                .position(|column| *column == sort.column)
+        });
+        let position = match (position, forward) {
+            (None, _) => 0,
+            (Some(position), true) => (position + 1) % visible_columns.len(),
+            (Some(position), false) => {
+                (position + visible_columns.len() - 1) % visible_columns.len()
+            }
+        };
+
+        let order_type = self
+            .sort
+            .as_ref()
+            .map_or(OrderType::Buy, |sort| sort.order_type);
+        self.sort = Some(Sort {
+            column: visible_columns[position],
+            order_type,
+        });
+        self.sort_banks();
+    }
+
+    fn set_order_type(&mut self, order_type: OrderType) {
+        match &mut self.sort {
+            Some(sort) => sort.order_type = order_type,
+            None => {
+                let column = self
+                    .visible
+                    .iter()
+                    .position(|visible| *visible)
+                    .unwrap_or(0);
+                self.sort = Some(Sort { column, order_type });
+            }
+        }
+        self.sort_banks();
+    }
+
+    fn select(&mut self, forward: bool) {
+        let count = self.shown_banks().len();
+        if count == 0 {
+            return;
+        }
+
+        let selected = self.table_state.selected().unwrap_or(0);
+        let selected = match forward {
+            true => (selected + 1).min(count - 1),
+            false => selected.saturating_sub(1),
+        };
+        self.table_state.select(Some(selected));
+    }
+
+    fn toggle_selected_bank(&mut self) {
+        let name = match self.selected_bank() {
+            Some(bank) => bank.get_name().to_string(),
+            None => return,
+        };
+
+        if !self.disabled.remove(&name) {
+            self.disabled.insert(name);
+        }
+        self.sort_banks();
+    }
+
+    async fn refresh(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        self.status = tr("tui.refreshing");
+        terminal.draw(|frame| self.draw(frame))?;
+
+        match fetch_banks(&self.requested).await {
+            Ok(banks) => {
+                self.order = fetch_order(&banks);
+                self.banks = banks;
+                self.sort_banks();
+                self.status = i18n::tr_with("tui.updated", chrono::Local::now().format("%H:%M:%S"));
+            }
+            Err(error) => self.status = i18n::tr_with("tui.refresh_failed", error),
+        }
+
+        Ok(())
+    }
+
+    /// Handles a key press, returns `false` when the application should exit.
+    async fn handle_key(&mut self, key: KeyCode, terminal: &mut DefaultTerminal) -> Result<bool> {
+        if self.detail {
+            if let KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') = key {
+                self.detail = false;
+            }
+            return Ok(true);
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Tab | KeyCode::Char('c') => {
+                let rate_types = self.rate_types();
+                let position = rate_types
+                    .iter()
+                    .position(|currency_type| *currency_type == self.currency_type);
+                self.currency_type = match position {
+                    Some(position) => rate_types[(position + 1) % rate_types.len()].clone(),
+                    None => CurrencyType::Cash,
+                };
+                self.sort_banks();
+            }
+            KeyCode::Char(digit @ '1'..='4') => {
+                let column = digit as usize - '1' as usize;
+                self.visible[column] = !self.visible[column];
+            }
+            KeyCode::Left => self.move_sort_column(false),
+            KeyCode::Right => self.move_sort_column(true),
+            KeyCode::Char('b') => self.set_order_type(OrderType::Buy),
+            KeyCode::Char('s') => self.set_order_type(OrderType::Sell),
+            KeyCode::Char('p') => self.set_order_type(OrderType::Spread),
+            KeyCode::Char('n') => {
+                self.sort = None;
+                self.sort_banks();
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.select(false),
+            KeyCode::Down | KeyCode::Char('j') => self.select(true),
+            KeyCode::Char(' ') => self.toggle_selected_bank(),
+            KeyCode::Char('h') => {
+                self.show_disabled = !self.show_disabled;
+                self.clamp_selection();
+            }
+            KeyCode::Enter => self.detail = self.selected_bank().is_some(),
+            KeyCode::Char('r') => self.refresh(terminal).await?,
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, table_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+
+        let rate_types = self.rate_types();
+        let selected_tab = rate_types
+            .iter()
+            .position(|currency_type| *currency_type == self.currency_type);
+        let tabs = Tabs::new(
+            rate_types
+                .iter()
+                .map(|currency_type| tr(&format!("header.{}", currency_type.key()))),
+        )
+        .select(selected_tab)
+        .highlight_style(Style::new().bold().light_green())
+        .block(Block::new().borders(Borders::ALL).title(" armbankrate "));
+        frame.render_widget(tabs, tabs_area);
+
+        self.draw_table(frame, table_area);
+
+        let status = Paragraph::new(vec![
+            Line::from(tr("tui.help").dark_gray()),
+            Line::from(self.status.as_str().light_cyan()),
+        ]);
+        frame.render_widget(status, status_area);
+
+        if self.detail {
+            self.draw_detail(frame);
+        }
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let visible_currencies = self.visible_currencies();
+
+        let mut header = vec![Cell::from(tr("column.bank"))];
+        for currency_name in &visible_currencies {
+            let mut title = format!("{currency_name:?}");
+            if let Some(sort) = &self.sort {
+                if &&CURRENCIES[sort.column] == currency_name {
+                    let key = match sort.order_type {
+                        OrderType::Buy => "tui.sorted_buy",
+                        OrderType::Sell => "tui.sorted_sell",
+                        OrderType::Spread => "tui.sorted_spread",
+                        OrderType::Deviation(_) => "tui.sorted_deviation",
+                    };
+                    title = format!("{title} {}", tr(key));
+                }
+            }
+            header.push(Cell::from(title));
+        }
+        let header = Row::new(header).style(Style::new().bold().light_cyan());
+
+        let rows: Vec<Row> = self
+            .shown_banks()
+            .into_iter()
+            .map(|bank| {
+                let disabled = self.disabled.contains(bank.get_name());
+                let currencies = self.currencies(bank);
+
+                let mut cells = vec![Cell::from(i18n::bank_name(bank.get_name())).light_yellow()];
+                for currency_name in &visible_currencies {
+                    cells.push(currency_cell(currencies.get_rate(currency_name)));
+                }
+
+                match disabled {
+                    true => Row::new(cells).style(Style::new().add_modifier(Modifier::DIM)),
+                    false => Row::new(cells),
+                }
+            })
+            .collect();
+
+        let mut widths = vec![Constraint::Length(14)];
+        widths.extend(visible_currencies.iter().map(|_| Constraint::Min(17)));
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::new().bg(Color::DarkGray))
+            .highlight_symbol("> ")
+            .block(Block::new().borders(Borders::ALL));
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame) {
+        let bank = match self.selected_bank() {
+            Some(bank) => bank,
+            None => return,
+        };
+
+        let [buy, sell, spread, spread_percent] =
+            i18n::columns(["buy", "sell", "spread", "spread_percent"]);
+        let header = Row::new([String::new(), buy, sell, spread, spread_percent])
+            .style(Style::new().bold().light_cyan());
+
+        let mut rows = Vec::new();
+        for currency_type in &bank.currency_types() {
+            let currencies = bank.currencies(currency_type);
+            rows.push(Row::new([Cell::from(rate_type_name(currency_type))
+                .bold()
+                .light_green()]));
+            for currency_name in &CURRENCIES {
+                let currency = currencies.get_rate(currency_name);
+                rows.push(spread_row(
+                    format!("{currency_name:?}"),
+                    currency.buy(),
+                    currency.sell(),
+                ));
+
+                // Rates for larger amounts follow the base rate of the currency
+                for tier in currency.tiers() {
+                    rows.push(
+                        spread_row(
+                            format!("  ≥ {}", format_rate(&Some(tier.min_amount()))),
+                            tier.buy(),
+                            tier.sell(),
+                        )
+                        .dim(),
+                    );
+                }
+            }
+        }
+
+        let area = centered(frame.area(), 70, rows.len() as u16 + 4);
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title(
+                    format!(" {} ", i18n::bank_name(bank.get_name()))
+                        .light_yellow()
+                        .bold(),
+                )
+                .title_bottom(bank.get_url()),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(table, area);
+    }
+}
+
+fn currency_cell(currency: &Currency) -> Cell<'static> {
+    Cell::from(Line::from(vec![
+        Span::from(format_rate(currency.buy())).light_green(),
+        Span::from(" / "),
+        Span::from(format_rate(currency.sell())).light_red(),
+    ]))
+}
+
+fn spread_row(title: String, buy: &Option<f64>, sell: &Option<f64>) -> Row<'static> {
+    let (spread, spread_percent) = match (buy, sell) {
+        (Some(buy), Some(sell)) if *buy > 0.0 => (
+            format!("{:.2}", sell - buy),
+            format!("{:.2}%", (sell - buy) / buy * 100.0),
+        ),
+        _ => (MISSING.to_string(), MISSING.to_string()),
+    };
+
+    Row::new([
+        Cell::from(title),
+        Cell::from(format_rate(buy)).light_green(),
+        Cell::from(format_rate(sell)).light_red(),
+        Cell::from(spread),
+        Cell::from(spread_percent),
+    ])
+}
+
+fn fetch_order(banks: &[Bank]) -> Vec<String> {
+    banks
+        .iter()
+        .map(|bank| bank.get_name().to_string())
+        .collect()
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+pub(crate) async fn handle_tui(banks: Vec<Banks>) -> Result<()> {
+    let parsed = fetch_banks(&banks).await?;
+    let mut app = App::new(banks, parsed);
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut app, &mut terminal).await;
+    ratatui::restore();
+
+    result
+}
+
+async fn run(app: &mut App, terminal: &mut DefaultTerminal) -> Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Some(Event::Key(key)) = next_event().await? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code, terminal).await? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Waits up to [`POLL_INTERVAL`] for a terminal event on a blocking thread, off the runtime.
+async fn next_event() -> Result<Option<Event>> {
+    let event = tokio::task::spawn_blocking(|| match event::poll(POLL_INTERVAL)? {
+        true => event::read().map(Some),
+        false => Ok(None),
+    })
+    .await??;
+
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn app() -> App {
+        let banks = [("unibank", 385.0), ("idbank", 387.0), ("evocabank", 386.0)]
+            .into_iter()
+            .map(|(name, buy)| {
+                let mut bank = Bank::from_str(name).unwrap();
+                bank.cash_currencies_mut().fill_from_currency(Currency::new(
+                    CurrencyName::USD,
+                    Some(buy),
+                    Some(buy + 5.0),
+                ));
+                bank
+            })
+            .collect();
+
+        App::new(Vec::new(), banks)
+    }
+
+    fn names(banks: &[&Bank]) -> Vec<String> {
+        banks
+            .iter()
+            .map(|bank| bank.get_name().to_lowercase())
+            .collect()
+    }
+
+    #[test]
+    fn clearing_the_sort_restores_fetch_order() {
+        let mut app = app();
+        app.set_order_type(OrderType::Buy);
+        assert_eq!(
+            names(&app.shown_banks()),
+            ["idbank", "evocabank", "unibank"]
+        );
+
+        app.sort = None;
+        app.sort_banks();
+        assert_eq!(
+            names(&app.shown_banks()),
+            ["unibank", "idbank", "evocabank"]
+        );
+    }
+
+    #[test]
+    fn disabled_banks_are_hidden_until_shown() {
+        let mut app = app();
+        app.table_state.select(Some(2));
+        app.toggle_selected_bank();
+
+        assert_eq!(names(&app.shown_banks()), ["unibank", "idbank"]);
+        assert_eq!(app.table_state.selected(), Some(1));
+
+        app.show_disabled = true;
+        assert_eq!(
+            names(&app.shown_banks()),
+            ["unibank", "idbank", "evocabank"]
+        );
+    }
+}
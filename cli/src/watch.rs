@@ -0,0 +1,119 @@
+use crate::table_builder::TableBuilder;
+use crate::{fetch_banks, i18n, Banks, CurrencyType, TableArgs};
+use anyhow::Result;
+use armbankrate_parser::{Bank, BankImpl, CurrencyBody};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+type QuoteKey = (
+    String,
+    armbankrate_parser::CurrencyType,
+    armbankrate_parser::CurrencyName,
+);
+
+/// Buy and sell rates of every bank from a single poll.
+#[derive(Default)]
+pub(crate) struct Snapshot {
+    quotes: HashMap<QuoteKey, (Option<f64>, Option<f64>)>,
+}
+
+impl Snapshot {
+    pub(crate) fn new(banks: &[Bank]) -> Self {
+        let mut quotes = HashMap::new();
+
+        for bank in banks {
+            for currency_type in bank.currency_types() {
+                let currencies = bank.currencies(&currency_type);
+                Self::insert_currencies(&mut quotes, bank.get_name(), currency_type, currencies);
+            }
+        }
+
+        Self { quotes }
+    }
+
+    fn insert_currencies(
+        quotes: &mut HashMap<QuoteKey, (Option<f64>, Option<f64>)>,
+        bank_name: &str,
+        currency_type: armbankrate_parser::CurrencyType,
+        currencies: &CurrencyBody,
+    ) {
+        for currency_name in [
+            armbankrate_parser::CurrencyName::USD,
+            armbankrate_parser::CurrencyName::GBP,
+            armbankrate_parser::CurrencyName::EUR,
+            armbankrate_parser::CurrencyName::RUB,
+        ] {
+            let currency = currencies.get_rate(&currency_name);
+            quotes.insert(
+                (bank_name.to_string(), currency_type.clone(), currency_name),
+                (*currency.buy(), *currency.sell()),
+            );
+        }
+    }
+
+    pub(crate) fn get(
+        &self,
+        bank_name: &str,
+        currency_type: &armbankrate_parser::CurrencyType,
+        currency_name: &armbankrate_parser::CurrencyName,
+    ) -> Option<&(Option<f64>, Option<f64>)> {
+        self.quotes.get(&(
+            bank_name.to_string(),
+            currency_type.clone(),
+            currency_name.clone(),
+        ))
+    }
+}
+
+/// Formats the change between two polls as an arrow with the delta.
+pub(crate) fn delta(current: Option<f64>, previous: Option<f64>) -> Option<String> {
+    let delta = current? - previous?;
+
+    match delta.partial_cmp(&0.0)? {
+        std::cmp::Ordering::Greater => Some(format!("▲{:.2}", delta).bright_green().to_string()),
+        std::cmp::Ordering::Less => Some(format!("▼{:.2}", -delta).bright_red().to_string()),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+pub(crate) async fn handle_watch(
+    banks: Vec<Banks>,
+    currency_type: CurrencyType,
+    table_args: TableArgs,
+    interval: Duration,
+) -> Result<()> {
+    let mut previous: Option<Snapshot> = None;
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+
+        let parsed = fetch_banks(&banks).await?;
+        let snapshot = Snapshot::new(&parsed);
+
+        let mut table_builder =
+            TableBuilder::new(parsed, currency_type.clone(), table_args.clone());
+        if let Some(previous) = previous.take() {
+            table_builder = table_builder.with_previous(previous);
+        }
+        let table = table_builder.build();
+
+        print!("{CLEAR_SCREEN}");
+        println!("{}", table);
+        println!(
+            "{}",
+            i18n::tr_args(
+                "watch.status",
+                &[
+                    &chrono::Local::now().format("%H:%M:%S"),
+                    &humantime::format_duration(interval.period()),
+                ]
+            )
+        );
+
+        previous = Some(snapshot);
+    }
+}
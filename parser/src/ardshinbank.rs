@@ -1,19 +1,31 @@
 use crate::{
-    BankBody, BankImpl, BankParseFail, Currency, CurrencyBody, CurrencyName, Error, CLIENT,
+    http, serialize_categories, BankBody, BankImpl, BankParseFail, Currency, CurrencyBody,
+    CurrencyName, CurrencyType, Error, RateCategories,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use scraper::Html;
 use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::str::FromStr;
 
+/// Keys of the rate lists in the API response.
+const CURRENCY_TYPE_KEYS: [(&str, CurrencyType); 4] = [
+    ("cash", CurrencyType::Cash),
+    ("no_cash", CurrencyType::Noncash),
+    ("card", CurrencyType::Card),
+    ("transfer", CurrencyType::Transfer),
+];
+
 #[derive(Debug, Serialize)]
 pub struct Ardshinbank {
     #[serde(skip_serializing)]
     body: BankBody,
     cash_currencies: CurrencyBody,
     no_cash_currencies: CurrencyBody,
+    #[serde(flatten, serialize_with = "serialize_categories")]
+    other_currencies: RateCategories,
 }
 
 impl Default for Ardshinbank {
@@ -22,61 +34,72 @@ impl Default for Ardshinbank {
             body: BankBody {
                 name: "Ardshinbank",
                 url: "https://website-api.ardshinbank.am/currency",
+                legal_name: "Ardshinbank CJSC",
+                code: "247",
+                swift: "ASHBAM22",
+                website: "https://ardshinbank.am/",
+                rates_page: "https://ardshinbank.am/",
+                address: "13 Grigor Lusavorich St, Yerevan 0015",
+                phone: "+374 12 222222",
+                rate_types: &[CurrencyType::Cash, CurrencyType::Noncash],
             },
             cash_currencies: Default::default(),
             no_cash_currencies: Default::default(),
+            other_currencies: Default::default(),
         }
     }
 }
 
-#[async_trait]
-impl BankImpl for Ardshinbank {
-    async fn parse(&mut self) -> Result<(), Error> {
-        let response = CLIENT
-            .get(self.get_url())
-            .send()
-            .await?
-            .json::<HashMap<String, Value>>()
-            .await?;
-        let cash_currencies = response["data"]["currencies"]["cash"]
-            .as_array()
-            .ok_or(BankParseFail)?;
-        let no_cash_currencies = response["data"]["currencies"]["no_cash"]
-            .as_array()
-            .ok_or(BankParseFail)?;
-
-        for currency in cash_currencies {
-            let currency_name =
-                CurrencyName::from_str(currency["type"].as_str().ok_or(BankParseFail)?);
-            let currency_name = match currency_name {
-                Ok(name) => name,
-                Err(_) => continue,
+impl Ardshinbank {
+    fn parse_response(&mut self, body: &str, fetched_at: DateTime<Utc>) -> Result<(), Error> {
+        let response = serde_json::from_str::<HashMap<String, Value>>(body)?;
+        let currencies = &response["data"]["currencies"];
+
+        // Cash and non-cash rates are always published, card and transfer ones only by some endpoints
+        for (key, currency_type) in CURRENCY_TYPE_KEYS {
+            let list = match currencies[key].as_array() {
+                Some(list) => list,
+                None if matches!(currency_type, CurrencyType::Cash | CurrencyType::Noncash) => {
+                    return Err(BankParseFail)
+                }
+                None => continue,
             };
 
-            let buy = serde_json::from_str::<f64>(currency["buy"].as_str().ok_or(BankParseFail)?)?;
-            let sell =
-                serde_json::from_str::<f64>(currency["sell"].as_str().ok_or(BankParseFail)?)?;
-            let currency = Currency::new(currency_name, buy.into(), sell.into());
-            self.cash_currencies.fill_from_currency(currency);
-        }
+            let body = self.currencies_mut(&currency_type);
+            for currency in list {
+                let currency_name =
+                    CurrencyName::from_str(currency["type"].as_str().ok_or(BankParseFail)?);
+                let currency_name = match currency_name {
+                    Ok(name) => name,
+                    Err(_) => continue,
+                };
 
-        for currency in no_cash_currencies {
-            let currency_name =
-                CurrencyName::from_str(currency["type"].as_str().ok_or(BankParseFail)?);
-            let currency_name = match currency_name {
-                Ok(name) => name,
-                Err(_) => continue,
-            };
+                let buy =
+                    serde_json::from_str::<f64>(currency["buy"].as_str().ok_or(BankParseFail)?)?;
+                let sell =
+                    serde_json::from_str::<f64>(currency["sell"].as_str().ok_or(BankParseFail)?)?;
+                body.fill_from_currency(Currency::new(currency_name, buy.into(), sell.into()));
+            }
 
-            let buy = serde_json::from_str::<f64>(currency["buy"].as_str().ok_or(BankParseFail)?)?;
-            let sell =
-                serde_json::from_str::<f64>(currency["sell"].as_str().ok_or(BankParseFail)?)?;
-            let currency = Currency::new(currency_name, buy.into(), sell.into());
-            self.no_cash_currencies.fill_from_currency(currency);
+            body.set_fetched_at(fetched_at);
         }
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl BankImpl for Ardshinbank {
+    async fn parse(&mut self) -> Result<(), Error> {
+        let response = http::fetch(
+            self.get_name(),
+            self.cache_ttl(),
+            http::Request::Get(self.get_url()),
+        )
+        .await?;
+
+        self.parse_response(&response.body, response.fetched_at)
+    }
     fn parse_cash(&mut self, _document: &Html) -> Result<(), Error> {
         unreachable!()
     }
@@ -93,6 +116,26 @@ impl BankImpl for Ardshinbank {
         &self.no_cash_currencies
     }
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cash_currencies
+    }
+
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.no_cash_currencies
+    }
+
+    fn other_currencies(&self) -> &RateCategories {
+        &self.other_currencies
+    }
+
+    fn other_currencies_mut(&mut self) -> &mut RateCategories {
+        &mut self.other_currencies
+    }
+
+    fn info(&self) -> &BankBody {
+        &self.body
+    }
+
     fn get_name(&self) -> &str {
         self.body.name
     }
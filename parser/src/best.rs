@@ -0,0 +1,311 @@
+use crate::validation::{median, Side, Verdict};
+use crate::{Bank, BankImpl, CurrencyName, CurrencyType, Error};
+use serde::Serialize;
+
+const CURRENCY_NAMES: [CurrencyName; 4] = [
+    CurrencyName::USD,
+    CurrencyName::EUR,
+    CurrencyName::RUB,
+    CurrencyName::GBP,
+];
+
+/// Rates closer than this are considered a tie.
+const TIE_TOLERANCE: f64 = 1e-9;
+
+/// Banks sharing the same rate at a given rank.
+#[derive(Debug, Clone, Serialize)]
+pub struct BestEntry {
+    rank: usize,
+    rate: f64,
+    banks: Vec<String>,
+}
+
+impl BestEntry {
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn banks(&self) -> &[String] {
+        &self.banks
+    }
+}
+
+/// Best rates for a customer of one currency, rate type and side.
+///
+/// The best buy rate is the highest one (selling currency to the bank),
+/// the best sell rate is the lowest one (buying currency from the bank).
+/// Margins are positive when the best rate is better for the customer.
+#[derive(Debug, Clone, Serialize)]
+pub struct BestRates {
+    currency_name: CurrencyName,
+    currency_type: CurrencyType,
+    side: Side,
+    entries: Vec<BestEntry>,
+    median: Option<f64>,
+    margin_to_second: Option<f64>,
+    margin_to_median: Option<f64>,
+}
+
+impl BestRates {
+    pub fn currency_name(&self) -> &CurrencyName {
+        &self.currency_name
+    }
+
+    pub fn currency_type(&self) -> &CurrencyType {
+        &self.currency_type
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn entries(&self) -> &[BestEntry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<&BestEntry> {
+        self.entries.first()
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        self.median
+    }
+
+    pub fn margin_to_second(&self) -> Option<f64> {
+        self.margin_to_second
+    }
+
+    pub fn margin_to_median(&self) -> Option<f64> {
+        self.margin_to_median
+    }
+}
+
+/// Picks the top `top` ranks for every currency, rate type and side.
+///
+/// Missing, non-positive and rejected quotes are ignored, as are rate types no bank publishes.
+pub fn best_rates(banks: &[Bank], top: usize) -> Vec<BestRates> {
+    let mut best_rates = Vec::new();
+
+    for currency_type in CurrencyType::ALL {
+        if !banks.iter().any(|bank| bank.has_currencies(&currency_type)) {
+            continue;
+        }
+
+        for currency_name in CURRENCY_NAMES {
+            for side in [Side::Buy, Side::Sell] {
+                best_rates.push(rank(banks, &currency_type, &currency_name, side, top));
+            }
+        }
+    }
+
+    best_rates
+}
+
+pub fn best_rates_json(best_rates: &[BestRates]) -> Result<String, Error> {
+    Ok(serde_json::to_string(best_rates)?)
+}
+
+fn rank(
+    banks: &[Bank],
+    currency_type: &CurrencyType,
+    currency_name: &CurrencyName,
+    side: Side,
+    top: usize,
+) -> BestRates {
+    let mut quotes: Vec<(f64, &str)> = banks
+        .iter()
+        .filter_map(|bank| {
+            let currency = bank.currencies(currency_type).get_rate(currency_name);
+            if currency.validation().verdict() == &Verdict::Rejected {
+                return None;
+            }
+
+            let rate = match side {
+                Side::Buy => *currency.buy(),
+                Side::Sell => *currency.sell(),
+            };
+            rate.filter(|rate| *rate > 0.0)
+                .map(|rate| (rate, bank.get_name()))
+        })
+        .collect();
+
+    // Best rate first
+    quotes.sort_by(|(a, _), (b, _)| match side {
+        Side::Buy => b.total_cmp(a),
+        Side::Sell => a.total_cmp(b),
+    });
+
+    let mut entries: Vec<BestEntry> = Vec::new();
+    for (rate, bank_name) in &quotes {
+        match entries.last_mut() {
+            Some(entry) if (entry.rate - rate).abs() < TIE_TOLERANCE => {
+                entry.banks.push(bank_name.to_string())
+            }
+            _ => entries.push(BestEntry {
+                rank: entries.len() + 1,
+                rate: *rate,
+                banks: vec![bank_name.to_string()],
+            }),
+        }
+    }
+
+    let median = median(quotes.iter().map(|(rate, _)| *rate).collect());
+    let margin = |other: f64| match side {
+        Side::Buy => entries[0].rate - other,
+        Side::Sell => other - entries[0].rate,
+    };
+    let margin_to_second = entries.get(1).map(|second| margin(second.rate));
+    let margin_to_median = match entries.is_empty() {
+        true => None,
+        false => median.map(margin),
+    };
+
+    entries.truncate(top);
+
+    BestRates {
+        currency_name: currency_name.clone(),
+        currency_type: currency_type.clone(),
+        side,
+        entries,
+        median,
+        margin_to_second,
+        margin_to_median,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Currency;
+    use std::str::FromStr;
+
+    /// Bank with the given cash buy and sell rates of USD.
+    fn bank(name: &str, buy: Option<f64>, sell: Option<f64>) -> Bank {
+        let mut bank = Bank::from_str(name).unwrap();
+        bank.cash_currencies_mut()
+            .fill_from_currency(Currency::new(CurrencyName::USD, buy, sell));
+
+        bank
+    }
+
+    fn usd(banks: &[Bank], side: Side, top: usize) -> BestRates {
+        rank(banks, &CurrencyType::Cash, &CurrencyName::USD, side, top)
+    }
+
+    fn ranks(best: &BestRates) -> Vec<(usize, f64, Vec<&str>)> {
+        best.entries()
+            .iter()
+            .map(|entry| {
+                let banks = entry.banks().iter().map(String::as_str).collect();
+                (entry.rank(), entry.rate(), banks)
+            })
+            .collect()
+    }
+
+    fn banks() -> Vec<Bank> {
+        vec![
+            bank("Idbank", Some(385.0), Some(391.0)),
+            bank("Unibank", Some(387.0), Some(392.0)),
+            bank("Evocabank", Some(387.0), None),
+            bank("Inecobank", Some(384.0), Some(389.0)),
+        ]
+    }
+
+    #[test]
+    fn banks_with_the_same_rate_share_a_rank() {
+        let best = usd(&banks(), Side::Buy, 10);
+
+        assert_eq!(
+            ranks(&best),
+            [
+                (1, 387.0, vec!["Unibank", "Evocabank"]),
+                (2, 385.0, vec!["Idbank"]),
+                (3, 384.0, vec!["Inecobank"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_lowest_sell_rate_is_the_best() {
+        let best = usd(&banks(), Side::Sell, 10);
+
+        assert_eq!(
+            ranks(&best),
+            [
+                (1, 389.0, vec!["Inecobank"]),
+                (2, 391.0, vec!["Idbank"]),
+                (3, 392.0, vec!["Unibank"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn margins_are_positive_for_the_customer() {
+        let buy = usd(&banks(), Side::Buy, 10);
+        // The median of 384, 385, 387 and 387 is 386
+        assert_eq!(buy.median(), Some(386.0));
+        assert_eq!(buy.margin_to_second(), Some(2.0));
+        assert_eq!(buy.margin_to_median(), Some(1.0));
+
+        let sell = usd(&banks(), Side::Sell, 10);
+        assert_eq!(sell.median(), Some(391.0));
+        assert_eq!(sell.margin_to_second(), Some(2.0));
+        assert_eq!(sell.margin_to_median(), Some(2.0));
+    }
+
+    #[test]
+    fn ranks_are_truncated_after_the_margins() {
+        let best = usd(&banks(), Side::Buy, 1);
+
+        assert_eq!(ranks(&best), [(1, 387.0, vec!["Unibank", "Evocabank"])]);
+        assert_eq!(best.margin_to_second(), Some(2.0));
+        assert_eq!(best.median(), Some(386.0));
+
+        // Margins are computed before the truncation, even when no rank is kept
+        let best = usd(&banks(), Side::Buy, 0);
+        assert!(best.entries().is_empty());
+        assert_eq!(best.margin_to_second(), Some(2.0));
+    }
+
+    #[test]
+    fn a_single_bank_has_no_second() {
+        let best = usd(&[bank("Idbank", Some(385.0), Some(391.0))], Side::Buy, 3);
+
+        assert_eq!(ranks(&best), [(1, 385.0, vec!["Idbank"])]);
+        assert_eq!(best.margin_to_second(), None);
+        assert_eq!(best.margin_to_median(), Some(0.0));
+    }
+
+    #[test]
+    fn missing_and_non_positive_rates_are_ignored() {
+        let banks = [
+            bank("Idbank", None, None),
+            bank("Unibank", Some(0.0), Some(-1.0)),
+        ];
+
+        for side in [Side::Buy, Side::Sell] {
+            let best = usd(&banks, side, 3);
+            assert!(best.entries().is_empty());
+            assert!(best.best().is_none());
+            assert_eq!(best.median(), None);
+            assert_eq!(best.margin_to_second(), None);
+            assert_eq!(best.margin_to_median(), None);
+        }
+    }
+
+    #[test]
+    fn unpublished_rate_types_are_skipped() {
+        let best_rates = best_rates(&banks(), 1);
+
+        // Cash and non-cash rates of four currencies, both sides
+        assert_eq!(best_rates.len(), 2 * 4 * 2);
+        assert!(best_rates.iter().all(|best| matches!(
+            best.currency_type(),
+            CurrencyType::Cash | CurrencyType::Noncash
+        )));
+    }
+}
@@ -0,0 +1,41 @@
+//! Synchronous wrappers around the async parsing functions.
+//!
+//! The functions run on a runtime owned by this module, so callers do not need one.
+//! Like other blocking clients they must not be called from within an async runtime.
+
+use crate::{Bank, Error};
+use once_cell::sync::OnceCell;
+use tokio::runtime::Runtime;
+
+static RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+/// One runtime is shared by all calls, so the pooled connections of the HTTP client stay usable.
+fn runtime() -> Result<&'static Runtime, Error> {
+    Ok(RUNTIME.get_or_try_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("armbankrate-blocking")
+            .enable_all()
+            .build()
+    })?)
+}
+
+/// Blocking version of [`crate::parse`].
+pub fn parse<T: ToString>(banks: &[T]) -> Result<Vec<Bank>, Error> {
+    runtime()?.block_on(crate::parse(banks))
+}
+
+/// Blocking version of [`crate::parse_all`].
+pub fn parse_all() -> Result<Vec<Bank>, Error> {
+    runtime()?.block_on(crate::parse_all())
+}
+
+/// Blocking version of [`crate::parse_json`].
+pub fn parse_json<T: ToString>(banks: &[T]) -> Result<String, Error> {
+    runtime()?.block_on(crate::parse_json(banks))
+}
+
+/// Blocking version of [`crate::parse_all_json`].
+pub fn parse_all_json() -> Result<String, Error> {
+    runtime()?.block_on(crate::parse_all_json())
+}
@@ -0,0 +1,247 @@
+//! Branch directories of the banks that publish them, with branch-specific cash rates.
+//!
+//! Branch lists are fetched apart from the rates, see [`BankImpl::parse_branches`].
+//!
+//! No bank provides a branch list yet, a provider gets one once a real response of its branch
+//! endpoint is captured as a test fixture.
+
+use crate::validation::{validate_banks, Side, Verdict};
+use crate::{Bank, BankImpl, CurrencyBody, CurrencyName, Error};
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Coordinates {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl Coordinates {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Branch {
+    name: String,
+    address: String,
+    coordinates: Option<Coordinates>,
+    /// Opening hours as published by the bank, e.g. `Mon-Fri 09:30-17:30`.
+    hours: Option<String>,
+    /// Cash rates of the branch when they differ from the bank-wide ones.
+    cash_currencies: Option<CurrencyBody>,
+}
+
+impl Branch {
+    pub fn new(name: String, address: String) -> Self {
+        Self {
+            name,
+            address,
+            coordinates: None,
+            hours: None,
+            cash_currencies: None,
+        }
+    }
+
+    pub fn with_coordinates(mut self, coordinates: Coordinates) -> Self {
+        self.coordinates = Some(coordinates);
+        self
+    }
+
+    pub fn with_hours(mut self, hours: String) -> Self {
+        self.hours = Some(hours);
+        self
+    }
+
+    pub fn with_cash_currencies(mut self, cash_currencies: CurrencyBody) -> Self {
+        self.cash_currencies = Some(cash_currencies);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        self.coordinates
+    }
+
+    pub fn hours(&self) -> Option<&str> {
+        self.hours.as_deref()
+    }
+
+    pub fn cash_currencies(&self) -> Option<&CurrencyBody> {
+        self.cash_currencies.as_ref()
+    }
+
+    pub(crate) fn cash_currencies_mut(&mut self) -> Option<&mut CurrencyBody> {
+        self.cash_currencies.as_mut()
+    }
+}
+
+/// Cash rate of a bank, at a single branch or at every branch without rates of its own.
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchRate {
+    bank: String,
+    branch: Option<String>,
+    address: Option<String>,
+    side: Side,
+    rate: f64,
+}
+
+impl BranchRate {
+    pub fn bank(&self) -> &str {
+        &self.bank
+    }
+
+    /// `None` for the bank-wide rate.
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+/// Parses the rates and branch lists of the given banks.
+///
+/// A failed branch list leaves the bank without branches, like a failed bank is left without rates.
+/// Branch rates are validated along with the bank-wide ones, against the same references.
+pub async fn parse<T: ToString>(banks: &[T]) -> Result<Vec<Bank>, Error> {
+    let mut banks: Vec<Bank> = banks
+        .iter()
+        .map(|bank| crate::bank_from_str(bank.to_string()))
+        .collect::<Result<Vec<Bank>, Error>>()?;
+
+    parse_with_branches(&mut banks).await;
+
+    Ok(banks)
+}
+
+/// Parses the rates and branch lists of all banks.
+pub async fn parse_all() -> Result<Vec<Bank>, Error> {
+    let mut banks = crate::get_bank_vec();
+
+    parse_with_branches(&mut banks).await;
+
+    Ok(banks)
+}
+
+async fn parse_with_branches(banks: &mut [Bank]) {
+    crate::parse_banks(banks).await;
+    parse_branch_lists(banks).await;
+    validate_banks(banks);
+}
+
+async fn parse_branch_lists(banks: &mut [Bank]) {
+    let futures = FuturesUnordered::new();
+    for bank in banks
+        .iter_mut()
+        .filter(|bank| bank.branches_url().is_some())
+    {
+        futures.push(async move {
+            if let Err(error) = bank.parse_branches().await {
+                tracing::warn!(bank = bank.get_name(), %error, "branches failed");
+            }
+        });
+    }
+    futures.collect::<Vec<()>>().await;
+}
+
+/// Cash rates of the currency ranked best first, branch-specific rates next to the bank-wide ones.
+///
+/// The best rate is the highest when the bank buys and the lowest when it sells.
+pub fn best_branch_rates(
+    banks: &[Bank],
+    currency_name: &CurrencyName,
+    side: Side,
+    top: usize,
+) -> Vec<BranchRate> {
+    let rate = |currencies: &CurrencyBody| {
+        let currency = currencies.get_rate(currency_name);
+        if currency.validation().verdict() == &Verdict::Rejected {
+            return None;
+        }
+
+        match side {
+            Side::Buy => *currency.buy(),
+            Side::Sell => *currency.sell(),
+        }
+        .filter(|rate| *rate > 0.0)
+    };
+
+    let mut rates = Vec::new();
+    for bank in banks {
+        if let Some(rate) = rate(bank.cash_currencies()) {
+            rates.push(BranchRate {
+                bank: bank.get_name().to_string(),
+                branch: None,
+                address: None,
+                side,
+                rate,
+            });
+        }
+
+        for branch in bank.branches() {
+            if let Some(rate) = branch.cash_currencies().and_then(rate) {
+                rates.push(BranchRate {
+                    bank: bank.get_name().to_string(),
+                    branch: Some(branch.name.clone()),
+                    address: Some(branch.address.clone()),
+                    side,
+                    rate,
+                });
+            }
+        }
+    }
+
+    rates.sort_by(|a, b| match side {
+        Side::Buy => b.rate.total_cmp(&a.rate),
+        Side::Sell => a.rate.total_cmp(&b.rate),
+    });
+    rates.truncate(top);
+
+    rates
+}
+
+/// Branches of the banks that publish them, by bank name.
+pub fn branches_json(banks: &[Bank]) -> Result<String, Error> {
+    let branches: BTreeMap<&str, &[Branch]> = banks
+        .iter()
+        .filter(|bank| !bank.branches().is_empty())
+        .map(|bank| (bank.get_name(), bank.branches()))
+        .collect();
+
+    Ok(serde_json::to_string(&branches)?)
+}
+
+pub fn best_branch_rates_json(rates: &[BranchRate]) -> Result<String, Error> {
+    Ok(serde_json::to_string(rates)?)
+}
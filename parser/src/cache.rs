@@ -0,0 +1,414 @@
+//! On-disk cache of bank responses.
+//!
+//! The cache is disabled by default, applications turn it on with [`set_mode`].
+//! Fresh entries are served without a request, expired ones are revalidated
+//! with `If-None-Match` / `If-Modified-Since` when the bank sent an `ETag` or `Last-Modified`.
+
+use crate::Error;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Time-to-live of cached responses for banks without their own.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(5 * 60);
+
+static CONFIG: Lazy<RwLock<CacheConfig>> = Lazy::new(|| RwLock::new(CacheConfig::default()));
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Responses are neither read from nor written to the cache.
+    #[default]
+    Disabled,
+    /// Fresh entries are used, expired ones are revalidated.
+    Enabled,
+    /// Every entry is revalidated regardless of its age.
+    Refresh,
+}
+
+impl CacheMode {
+    /// Whether responses are read from and written to the cache.
+    pub fn is_enabled(&self) -> bool {
+        *self != CacheMode::Disabled
+    }
+
+    /// Whether `entry` may be used without asking the bank, when it is younger than `ttl`.
+    pub(crate) fn serves(&self, entry: &CacheEntry, ttl: Duration) -> bool {
+        *self == CacheMode::Enabled && entry.age() < ttl
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheConfig {
+    mode: CacheMode,
+    dir: Option<PathBuf>,
+    default_ttl: Option<Duration>,
+    ttls: HashMap<String, Duration>,
+}
+
+pub fn set_mode(mode: CacheMode) {
+    CONFIG.write().unwrap().mode = mode;
+}
+
+pub fn mode() -> CacheMode {
+    CONFIG.read().unwrap().mode
+}
+
+/// Overrides the cache directory, see [`dir`] for the default one.
+pub fn set_dir(dir: PathBuf) {
+    CONFIG.write().unwrap().dir = Some(dir);
+}
+
+/// Directory of the cache, `$XDG_CACHE_HOME/armbankrate` or `~/.cache/armbankrate` by default.
+pub fn dir() -> PathBuf {
+    if let Some(dir) = &CONFIG.read().unwrap().dir {
+        return dir.clone();
+    }
+
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("armbankrate")
+}
+
+/// Sets the time-to-live for the bank with the given name (case insensitive).
+pub fn set_ttl(bank: &str, ttl: Duration) {
+    CONFIG
+        .write()
+        .unwrap()
+        .ttls
+        .insert(bank.to_lowercase(), ttl);
+}
+
+/// Sets the time-to-live for every bank without its own.
+pub fn set_default_ttl(ttl: Duration) {
+    CONFIG.write().unwrap().default_ttl = Some(ttl);
+}
+
+/// Time-to-live for `bank`, `bank_ttl` is the default of the bank implementation.
+pub(crate) fn ttl(bank: &str, bank_ttl: Duration) -> Duration {
+    let config = CONFIG.read().unwrap();
+
+    config
+        .ttls
+        .get(&bank.to_lowercase())
+        .copied()
+        .or(config.default_ttl)
+        .unwrap_or(bank_ttl)
+}
+
+/// Cached response of a single request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    bank: String,
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: DateTime<Utc>,
+    ttl: Duration,
+    body: String,
+}
+
+impl CacheEntry {
+    pub(crate) fn new(
+        bank: &str,
+        url: &str,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        ttl: Duration,
+        body: String,
+    ) -> Self {
+        Self {
+            bank: bank.to_string(),
+            url: url.to_string(),
+            etag,
+            last_modified,
+            fetched_at: Utc::now(),
+            ttl,
+            body,
+        }
+    }
+
+    pub fn bank(&self) -> &str {
+        &self.bank
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+
+    /// Time the response was downloaded or last revalidated.
+    pub fn fetched_at(&self) -> DateTime<Utc> {
+        self.fetched_at
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.fetched_at).to_std().unwrap_or_default()
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.age() < self.ttl
+    }
+
+    /// Marks the entry as revalidated by the server.
+    pub(crate) fn touch(&mut self, ttl: Duration) {
+        self.fetched_at = Utc::now();
+        self.ttl = ttl;
+    }
+}
+
+/// File name of the entry for a request, the bank name keeps it readable.
+///
+/// The request is hashed with 64-bit FNV-1a, which unlike the std hashers is the same for every
+/// build, so entries survive toolchain upgrades.
+pub(crate) fn key(bank: &str, request: &str) -> String {
+    let hash = request
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+
+    format!("{}-{hash:016x}", bank.to_lowercase())
+}
+
+pub(crate) fn load(key: &str) -> Option<CacheEntry> {
+    load_from(&dir(), key)
+}
+
+pub(crate) fn store(key: &str, entry: &CacheEntry) -> Result<(), Error> {
+    store_in(&dir(), key, entry)
+}
+
+fn load_from(dir: &Path, key: &str) -> Option<CacheEntry> {
+    let content = std::fs::read_to_string(dir.join(format!("{key}.json"))).ok()?;
+
+    serde_json::from_str(&content).ok()
+}
+
+/// Writes through a temporary file, so concurrent runs never read a partial entry.
+fn store_in(dir: &Path, key: &str, entry: &CacheEntry) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)?;
+
+    let temporary = dir.join(format!("{key}.json.{}", std::process::id()));
+    std::fs::write(&temporary, serde_json::to_string(entry)?)?;
+    std::fs::rename(temporary, dir.join(format!("{key}.json")))?;
+
+    Ok(())
+}
+
+/// All cached entries, sorted by bank name.
+pub fn entries() -> Result<Vec<CacheEntry>, Error> {
+    let mut entries: Vec<CacheEntry> = cached_files(&dir())?
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect();
+    entries.sort_by(|a, b| a.bank.cmp(&b.bank).then_with(|| a.url.cmp(&b.url)));
+
+    Ok(entries)
+}
+
+/// Removes the entries of `bank` (case insensitive), or all entries, returning how many were removed.
+pub fn clear(bank: Option<&str>) -> Result<usize, Error> {
+    clear_in(&dir(), bank)
+}
+
+fn clear_in(dir: &Path, bank: Option<&str>) -> Result<usize, Error> {
+    let mut removed = 0;
+    for (path, entry) in cached_files(dir)? {
+        if bank.is_some_and(|bank| !entry.bank.eq_ignore_ascii_case(bank)) {
+            continue;
+        }
+
+        std::fs::remove_file(path)?;
+        removed += 1;
+    }
+
+    Ok(removed)
+}
+
+/// Cache entries in `dir` with their paths, other files sharing the directory are left out.
+fn cached_files(dir: &Path) -> Result<Vec<(PathBuf, CacheEntry)>, Error> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for file in std::fs::read_dir(dir)? {
+        let path = file?.path();
+        if path.extension() != Some("json".as_ref()) {
+            continue;
+        }
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) => {
+                tracing::warn!(path = %path.display(), %error, "cache entry skipped");
+                continue;
+            }
+        };
+        if let Ok(entry) = serde_json::from_str::<CacheEntry>(&content) {
+            files.push((path, entry));
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty directory of its own for each test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("armbankrate-cache-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn entry(ttl: Duration, age: Duration) -> CacheEntry {
+        let mut entry = CacheEntry::new(
+            "Idbank",
+            "https://idbank.am/",
+            Some("\"abc\"".to_string()),
+            None,
+            ttl,
+            "<html></html>".to_string(),
+        );
+        entry.fetched_at = Utc::now() - chrono::Duration::from_std(age).unwrap();
+        entry
+    }
+
+    #[test]
+    fn keys_are_stable() {
+        assert_eq!(key("Inecobank", ""), "inecobank-cbf29ce484222325");
+        assert_eq!(key("Inecobank", "a"), "inecobank-af63dc4c8601ec8c");
+        assert_eq!(
+            key(
+                "Ardshinbank",
+                "GET https://website-api.ardshinbank.am/currency"
+            ),
+            "ardshinbank-9ef5fc3f4408da55"
+        );
+        assert_ne!(
+            key("Idbank", "POST https://idbank.am/ RATE_TYPE=CASH"),
+            key("Idbank", "POST https://idbank.am/ RATE_TYPE=NO_CASH")
+        );
+    }
+
+    #[test]
+    fn entries_are_stored_and_loaded() {
+        let dir = temp_dir("store");
+        let stored = entry(DEFAULT_TTL, Duration::ZERO);
+
+        assert!(load_from(&dir, "idbank-1").is_none());
+        store_in(&dir, "idbank-1", &stored).unwrap();
+
+        let loaded = load_from(&dir, "idbank-1").unwrap();
+        assert_eq!(loaded.bank(), "Idbank");
+        assert_eq!(loaded.etag(), Some("\"abc\""));
+        assert_eq!(loaded.body(), stored.body());
+        assert_eq!(loaded.fetched_at(), stored.fetched_at());
+
+        // Only the entry is left behind, not the temporary file
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::write(dir.join("idbank-2.json"), "{").unwrap();
+        assert!(load_from(&dir, "idbank-2").is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn entries_expire_after_their_ttl() {
+        let ttl = Duration::from_secs(5 * 60);
+
+        let fresh = entry(ttl, Duration::from_secs(60));
+        assert!(fresh.is_fresh());
+        assert!(CacheMode::Enabled.serves(&fresh, ttl));
+
+        let expired = entry(ttl, Duration::from_secs(10 * 60));
+        assert!(!expired.is_fresh());
+        assert!(!CacheMode::Enabled.serves(&expired, ttl));
+
+        // The configured TTL is used, not the one the entry was stored with
+        assert!(CacheMode::Enabled.serves(&expired, Duration::from_secs(60 * 60)));
+    }
+
+    #[test]
+    fn refresh_mode_revalidates_fresh_entries() {
+        let fresh = entry(DEFAULT_TTL, Duration::ZERO);
+
+        assert!(CacheMode::Refresh.is_enabled());
+        assert!(!CacheMode::Refresh.serves(&fresh, DEFAULT_TTL));
+    }
+
+    #[test]
+    fn disabled_mode_serves_nothing() {
+        let fresh = entry(DEFAULT_TTL, Duration::ZERO);
+
+        assert!(!CacheMode::Disabled.is_enabled());
+        assert!(!CacheMode::Disabled.serves(&fresh, DEFAULT_TTL));
+    }
+
+    #[test]
+    fn clear_removes_only_cache_entries() {
+        let dir = temp_dir("clear");
+        store_in(&dir, "idbank-1", &entry(DEFAULT_TTL, Duration::ZERO)).unwrap();
+        let mut other = entry(DEFAULT_TTL, Duration::ZERO);
+        other.bank = "Unibank".to_string();
+        store_in(&dir, "unibank-1", &other).unwrap();
+        std::fs::write(dir.join("notes.json"), r#"{"note": "keep"}"#).unwrap();
+        std::fs::write(dir.join("snapshot-2026.json"), "[]").unwrap();
+        // Unreadable as text, skipped rather than failing the listing
+        std::fs::write(dir.join("binary.json"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let banks: Vec<String> = cached_files(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|(_, entry)| entry.bank)
+            .collect();
+        assert_eq!(banks.len(), 2);
+
+        assert_eq!(clear_in(&dir, Some("IDBANK")).unwrap(), 1);
+        assert_eq!(clear_in(&dir, None).unwrap(), 1);
+        assert_eq!(clear_in(&dir, None).unwrap(), 0);
+        for name in ["notes.json", "snapshot-2026.json", "binary.json"] {
+            assert!(dir.join(name).exists());
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn touch_renews_the_entry() {
+        let mut entry = entry(Duration::from_secs(60), Duration::from_secs(10 * 60));
+
+        entry.touch(Duration::from_secs(120));
+
+        assert!(entry.is_fresh());
+        assert_eq!(entry.ttl(), Duration::from_secs(120));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{BankBody, BankImpl, BankParseFail, Currency, CurrencyBody, CurrencyName, Error};
+use crate::{
+    serialize_categories, trace_rows, BankBody, BankImpl, BankParseFail, Currency, CurrencyBody,
+    CurrencyName, CurrencyType, Error, RateCategories,
+};
 use async_trait::async_trait;
 use scraper::{ElementRef, Html, Selector};
 use serde::Serialize;
@@ -10,6 +13,8 @@ pub struct Conversebank {
     body: BankBody,
     cash_currencies: CurrencyBody,
     cashless_currencies: CurrencyBody,
+    #[serde(flatten, serialize_with = "serialize_categories")]
+    other_currencies: RateCategories,
     #[serde(skip_serializing)]
     main_selector: Selector,
 }
@@ -42,9 +47,18 @@ impl Default for Conversebank {
             body: BankBody {
                 name: "Conversebank",
                 url: "https://www.conversebank.am/ru/exchange-rate/",
+                legal_name: "Converse Bank CJSC",
+                code: "193",
+                swift: "COVBAM22",
+                website: "https://www.conversebank.am/",
+                rates_page: "https://www.conversebank.am/ru/exchange-rate/",
+                address: "26/1 Vazgen Sargsyan St, Yerevan 0010",
+                phone: "+374 10 511211",
+                rate_types: &[CurrencyType::Cash, CurrencyType::Noncash],
             },
             cash_currencies: Default::default(),
             cashless_currencies: Default::default(),
+            other_currencies: Default::default(),
             main_selector: Selector::parse(
                 "#main_static_content > table:nth-child(5) > tbody > tr",
             )
@@ -56,6 +70,8 @@ impl Default for Conversebank {
 #[async_trait]
 impl BankImpl for Conversebank {
     fn parse_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.main_selector, CurrencyType::Cash);
+
         for element in document.select(&self.main_selector).skip(2).take(4) {
             let currency_name = match self.parse_currency_name_from_element(&element) {
                 Ok(currency_name) => currency_name,
@@ -73,6 +89,8 @@ impl BankImpl for Conversebank {
     }
 
     fn parse_no_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.main_selector, CurrencyType::Noncash);
+
         for element in document.select(&self.main_selector).skip(2).take(4) {
             let currency_name = match self.parse_currency_name_from_element(&element) {
                 Ok(currency_name) => currency_name,
@@ -96,6 +114,26 @@ impl BankImpl for Conversebank {
         &self.cashless_currencies
     }
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cash_currencies
+    }
+
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cashless_currencies
+    }
+
+    fn other_currencies(&self) -> &RateCategories {
+        &self.other_currencies
+    }
+
+    fn other_currencies_mut(&mut self) -> &mut RateCategories {
+        &mut self.other_currencies
+    }
+
+    fn info(&self) -> &BankBody {
+        &self.body
+    }
+
     fn get_name(&self) -> &str {
         self.body.name
     }
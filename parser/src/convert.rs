@@ -0,0 +1,238 @@
+//! Conversion of an amount of a currency to AMD at every bank's rate.
+
+use crate::validation::Verdict;
+use crate::{Bank, BankImpl, CurrencyName, CurrencyType, Error};
+use serde::Serialize;
+
+/// Side of the exchange from the customer's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Exchange {
+    /// The customer sells the currency to the bank, at its buy rate.
+    Sell,
+    /// The customer buys the currency from the bank, at its sell rate.
+    Buy,
+}
+
+/// AMD a bank pays or asks for the converted amount.
+#[derive(Debug, Clone, Serialize)]
+pub struct Conversion {
+    bank: String,
+    currency_type: CurrencyType,
+    rate: f64,
+    total: f64,
+    /// Minimum amount of the tier the rate comes from, if any.
+    tier: Option<f64>,
+}
+
+impl Conversion {
+    pub fn bank(&self) -> &str {
+        &self.bank
+    }
+
+    pub fn currency_type(&self) -> &CurrencyType {
+        &self.currency_type
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    pub fn tier(&self) -> Option<f64> {
+        self.tier
+    }
+}
+
+/// Converts `amount` units of the currency at the tier of every bank matching it, best deal first.
+///
+/// Every bank is quoted once for each of the given rate types it publishes,
+/// rejected rates and banks that do not quote the currency are left out.
+pub fn convert(
+    banks: &[Bank],
+    currency_types: &[CurrencyType],
+    currency_name: &CurrencyName,
+    exchange: Exchange,
+    amount: f64,
+) -> Vec<Conversion> {
+    let mut conversions: Vec<Conversion> = currency_types
+        .iter()
+        .flat_map(|currency_type| banks.iter().map(move |bank| (currency_type, bank)))
+        .filter(|(currency_type, bank)| bank.has_currencies(currency_type))
+        .filter_map(|(currency_type, bank)| {
+            let currency = bank.currencies(currency_type).get_rate(currency_name);
+            if currency.validation().verdict() == &Verdict::Rejected {
+                return None;
+            }
+
+            let rate = match exchange {
+                Exchange::Sell => *currency.buy_for(amount),
+                Exchange::Buy => *currency.sell_for(amount),
+            }
+            .filter(|rate| *rate > 0.0)?;
+            let tier = currency
+                .tier_for(amount)
+                .filter(|tier| {
+                    let tier_rate = match exchange {
+                        Exchange::Sell => tier.buy(),
+                        Exchange::Buy => tier.sell(),
+                    };
+                    tier_rate.is_some()
+                })
+                .map(|tier| tier.min_amount());
+
+            Some(Conversion {
+                bank: bank.get_name().to_string(),
+                currency_type: currency_type.clone(),
+                rate,
+                total: rate * amount,
+                tier,
+            })
+        })
+        .collect();
+
+    // The customer wants the most AMD when selling and to pay the least when buying
+    conversions.sort_by(|a, b| match exchange {
+        Exchange::Sell => b.total.total_cmp(&a.total),
+        Exchange::Buy => a.total.total_cmp(&b.total),
+    });
+
+    conversions
+}
+
+pub fn conversions_json(conversions: &[Conversion]) -> Result<String, Error> {
+    Ok(serde_json::to_string(conversions)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::validate_banks;
+    use crate::{Currency, RateTier};
+    use std::str::FromStr;
+
+    fn bank(name: &str, currency_type: &CurrencyType, currency: Currency) -> Bank {
+        let mut bank = Bank::from_str(name).unwrap();
+        bank.currencies_mut(currency_type)
+            .fill_from_currency(currency);
+
+        bank
+    }
+
+    fn usd(buy: f64, sell: f64) -> Currency {
+        Currency::new(CurrencyName::USD, Some(buy), Some(sell))
+    }
+
+    fn totals(conversions: &[Conversion]) -> Vec<(&str, f64, Option<f64>)> {
+        conversions
+            .iter()
+            .map(|conversion| (conversion.bank(), conversion.total(), conversion.tier()))
+            .collect()
+    }
+
+    fn banks() -> Vec<Bank> {
+        vec![
+            bank(
+                "ardshinbank",
+                &CurrencyType::Cash,
+                usd(385.0, 390.0).with_tiers(vec![RateTier::new(1000.0, Some(387.0), None)]),
+            ),
+            bank("evocabank", &CurrencyType::Cash, usd(386.0, 389.0)),
+            bank(
+                "inecobank",
+                &CurrencyType::Cash,
+                Currency::with_unit(CurrencyName::USD, Some(3840.0), Some(3880.0), 10),
+            ),
+        ]
+    }
+
+    fn cash(banks: &[Bank], exchange: Exchange, amount: f64) -> Vec<Conversion> {
+        convert(
+            banks,
+            &[CurrencyType::Cash],
+            &CurrencyName::USD,
+            exchange,
+            amount,
+        )
+    }
+
+    #[test]
+    fn selling_ranks_highest_total_first() {
+        let banks = banks();
+
+        assert_eq!(
+            totals(&cash(&banks, Exchange::Sell, 100.0)),
+            vec![
+                ("Evocabank", 38600.0, None),
+                ("Ardshinbank", 38500.0, None),
+                ("Inecobank", 38400.0, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn buying_ranks_lowest_total_first() {
+        let banks = banks();
+
+        assert_eq!(
+            totals(&cash(&banks, Exchange::Buy, 100.0)),
+            vec![
+                ("Inecobank", 38800.0, None),
+                ("Evocabank", 38900.0, None),
+                ("Ardshinbank", 39000.0, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn amount_at_tier_minimum_converts_at_tier_rate() {
+        let banks = banks();
+        let conversions = cash(&banks, Exchange::Sell, 1000.0);
+
+        assert_eq!(
+            totals(&conversions)[0],
+            ("Ardshinbank", 387_000.0, Some(1000.0))
+        );
+        // The tier has no sell rate, buying falls back to the base rate
+        let conversions = cash(&banks, Exchange::Buy, 1000.0);
+        assert!(conversions
+            .iter()
+            .all(|conversion| conversion.tier().is_none()));
+    }
+
+    #[test]
+    fn rejected_rates_are_left_out() {
+        let mut banks = banks();
+        banks.push(bank("idbank", &CurrencyType::Cash, usd(395.0, 380.0)));
+        validate_banks(&mut banks);
+
+        let conversions = cash(&banks, Exchange::Sell, 100.0);
+        assert_eq!(conversions.len(), 3);
+        assert!(conversions
+            .iter()
+            .all(|conversion| conversion.bank() != "Idbank"));
+    }
+
+    #[test]
+    fn every_rate_type_is_quoted() {
+        let mut banks = banks();
+        banks[1]
+            .currencies_mut(&CurrencyType::Noncash)
+            .fill_from_currency(usd(387.0, 388.0));
+
+        let conversions = convert(
+            &banks,
+            &[CurrencyType::Cash, CurrencyType::Noncash],
+            &CurrencyName::USD,
+            Exchange::Sell,
+            100.0,
+        );
+        let first = &conversions[0];
+        assert_eq!(
+            (first.bank(), first.currency_type(), first.total()),
+            ("Evocabank", &CurrencyType::Noncash, 38700.0)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::snapshot::{Quote, QuoteKey, Snapshot};
+use crate::validation::Side;
+use crate::Error;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RateChange {
+    key: QuoteKey,
+    side: Side,
+    before: f64,
+    after: f64,
+    change: f64,
+    percent: Option<f64>,
+}
+
+impl RateChange {
+    pub fn key(&self) -> &QuoteKey {
+        &self.key
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn before(&self) -> f64 {
+        self.before
+    }
+
+    pub fn after(&self) -> f64 {
+        self.after
+    }
+
+    pub fn change(&self) -> f64 {
+        self.change
+    }
+
+    /// Change relative to the older rate, `None` when the older rate is zero.
+    pub fn percent(&self) -> Option<f64> {
+        self.percent
+    }
+}
+
+/// One side of a quote, e.g. the buy rate of USD in cash at a bank.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SideKey {
+    key: QuoteKey,
+    side: Side,
+}
+
+impl SideKey {
+    pub fn key(&self) -> &QuoteKey {
+        &self.key
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+}
+
+/// Differences between an older and a newer snapshot.
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct SnapshotDiff {
+    changes: Vec<RateChange>,
+    added_banks: Vec<String>,
+    missing_banks: Vec<String>,
+    appeared: Vec<QuoteKey>,
+    vanished: Vec<QuoteKey>,
+    appeared_sides: Vec<SideKey>,
+    vanished_sides: Vec<SideKey>,
+}
+
+impl SnapshotDiff {
+    /// Changes of every rate present in both snapshots, including unchanged ones.
+    pub fn changes(&self) -> &[RateChange] {
+        &self.changes
+    }
+
+    pub fn added_banks(&self) -> &[String] {
+        &self.added_banks
+    }
+
+    pub fn missing_banks(&self) -> &[String] {
+        &self.missing_banks
+    }
+
+    /// Currencies quoted only in the newer snapshot, added banks are not repeated here.
+    pub fn appeared(&self) -> &[QuoteKey] {
+        &self.appeared
+    }
+
+    /// Currencies quoted only in the older snapshot, missing banks are not repeated here.
+    pub fn vanished(&self) -> &[QuoteKey] {
+        &self.vanished
+    }
+
+    /// Buy or sell rates quoted only in the newer snapshot while the other side was quoted in both.
+    pub fn appeared_sides(&self) -> &[SideKey] {
+        &self.appeared_sides
+    }
+
+    /// Buy or sell rates quoted only in the older snapshot while the other side was quoted in both.
+    pub fn vanished_sides(&self) -> &[SideKey] {
+        &self.vanished_sides
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+pub fn diff(old: &Snapshot, new: &Snapshot) -> SnapshotDiff {
+    let mut diff = SnapshotDiff {
+        added_banks: difference(new.banks(), old.banks()),
+        missing_banks: difference(old.banks(), new.banks()),
+        ..Default::default()
+    };
+
+    let empty = Quote::default();
+    for (key, new_quote) in new.quotes() {
+        let old_quote = old.quotes().get(key).unwrap_or(&empty);
+
+        if old_quote.is_empty() && !new_quote.is_empty() {
+            if !diff.added_banks.iter().any(|bank| bank == key.bank()) {
+                diff.appeared.push(key.clone());
+            }
+            continue;
+        }
+
+        for (side, old_rate, new_rate) in [
+            (Side::Buy, old_quote.buy(), new_quote.buy()),
+            (Side::Sell, old_quote.sell(), new_quote.sell()),
+        ] {
+            let side_key = || SideKey {
+                key: key.clone(),
+                side,
+            };
+
+            match (old_rate, new_rate) {
+                (Some(old_rate), Some(new_rate)) => {
+                    let change = new_rate - old_rate;
+                    let percent = match old_rate == 0.0 {
+                        true => None,
+                        false => Some(change / old_rate * 100.0),
+                    };
+
+                    diff.changes.push(RateChange {
+                        key: key.clone(),
+                        side,
+                        before: old_rate,
+                        after: new_rate,
+                        change,
+                        percent,
+                    });
+                }
+                (Some(_), None) if !new_quote.is_empty() => diff.vanished_sides.push(side_key()),
+                (None, Some(_)) => diff.appeared_sides.push(side_key()),
+                _ => {}
+            }
+        }
+    }
+
+    for (key, old_quote) in old.quotes() {
+        let new_quote = new.quotes().get(key).unwrap_or(&empty);
+
+        let bank_missing = diff.missing_banks.iter().any(|bank| bank == key.bank());
+        if !old_quote.is_empty() && new_quote.is_empty() && !bank_missing {
+            diff.vanished.push(key.clone());
+        }
+    }
+
+    diff
+}
+
+fn difference(a: &[String], b: &[String]) -> Vec<String> {
+    a.iter().filter(|name| !b.contains(name)).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot(value: serde_json::Value) -> Snapshot {
+        Snapshot::from_json(&value.to_string()).unwrap()
+    }
+
+    fn old() -> Snapshot {
+        snapshot(json!({
+            "Idbank": {
+                "cash_currencies": {
+                    "usd": {"buy": 385.0, "sell": 391.0},
+                    "eur": {"buy": 418.0, "sell": 428.0},
+                    "gbp": {"buy": 0.0, "sell": 500.0},
+                    "chf": {"buy": 430.0, "sell": 445.0},
+                    "cad": {"sell": 290.0}
+                }
+            },
+            "Unibank": {
+                "cash_currencies": {"usd": {"buy": 386.0, "sell": 390.0}}
+            }
+        }))
+    }
+
+    fn new() -> Snapshot {
+        snapshot(json!({
+            "Idbank": {
+                "cash_currencies": {
+                    "usd": {"buy": 387.0, "sell": 391.0},
+                    "rub": {"buy": 4.7, "sell": 5.1},
+                    "gbp": {"buy": 490.0, "sell": 500.0},
+                    "chf": {"sell": 446.0},
+                    "cad": {"buy": 280.0, "sell": 291.0}
+                }
+            },
+            "Evocabank": {
+                "cash_currencies": {"usd": {"buy": 386.5, "sell": 390.5}}
+            }
+        }))
+    }
+
+    fn change<'a>(diff: &'a SnapshotDiff, currency: &str, side: Side) -> &'a RateChange {
+        diff.changes()
+            .iter()
+            .find(|change| change.key().currency() == currency && change.side() == side)
+            .unwrap()
+    }
+
+    #[test]
+    fn changed_rates_have_absolute_and_percent_changes() {
+        let diff = diff(&old(), &new());
+
+        let usd_buy = change(&diff, "USD", Side::Buy);
+        assert_eq!(usd_buy.key().bank(), "Idbank");
+        assert_eq!((usd_buy.before(), usd_buy.after()), (385.0, 387.0));
+        assert_eq!(usd_buy.change(), 2.0);
+        assert!((usd_buy.percent().unwrap() - 2.0 / 385.0 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unchanged_rates_are_reported_with_no_change() {
+        let diff = diff(&old(), &new());
+
+        let usd_sell = change(&diff, "USD", Side::Sell);
+        assert_eq!((usd_sell.change(), usd_sell.percent()), (0.0, Some(0.0)));
+        // Only rates present in both snapshots are compared: USD, GBP, CHF sell and CAD sell of Idbank
+        assert_eq!(diff.changes().len(), 6);
+    }
+
+    #[test]
+    fn a_zero_rate_has_no_percent_change() {
+        let diff = diff(&old(), &new());
+
+        let gbp_buy = change(&diff, "GBP", Side::Buy);
+        assert_eq!(gbp_buy.change(), 490.0);
+        assert_eq!(gbp_buy.percent(), None);
+    }
+
+    #[test]
+    fn one_sided_changes_are_reported() {
+        let diff = diff(&old(), &new());
+
+        let sides = |sides: &[SideKey]| -> Vec<(String, Side)> {
+            sides
+                .iter()
+                .map(|side| (side.key().currency().to_string(), side.side()))
+                .collect()
+        };
+        assert_eq!(
+            sides(diff.vanished_sides()),
+            [("CHF".to_string(), Side::Buy)]
+        );
+        assert_eq!(
+            sides(diff.appeared_sides()),
+            [("CAD".to_string(), Side::Buy)]
+        );
+        // The other side is still compared
+        assert_eq!(change(&diff, "CHF", Side::Sell).change(), 1.0);
+        assert_eq!(change(&diff, "CAD", Side::Sell).change(), 1.0);
+    }
+
+    #[test]
+    fn added_and_missing_banks_are_listed_once() {
+        let diff = diff(&old(), &new());
+
+        assert_eq!(diff.added_banks(), ["Evocabank"]);
+        assert_eq!(diff.missing_banks(), ["Unibank"]);
+        // The quotes of added and missing banks are not repeated
+        assert!(diff
+            .appeared()
+            .iter()
+            .chain(diff.vanished())
+            .all(|key| key.bank() == "Idbank"));
+    }
+
+    #[test]
+    fn appeared_and_vanished_currencies() {
+        let diff = diff(&old(), &new());
+
+        let appeared: Vec<&str> = diff.appeared().iter().map(QuoteKey::currency).collect();
+        let vanished: Vec<&str> = diff.vanished().iter().map(QuoteKey::currency).collect();
+        assert_eq!(appeared, ["RUB"]);
+        assert_eq!(vanished, ["EUR"]);
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_differences() {
+        let diff = diff(&old(), &old());
+
+        assert!(diff.changes().iter().all(|change| change.change() == 0.0));
+        assert!(diff.added_banks().is_empty() && diff.missing_banks().is_empty());
+        assert!(diff.appeared().is_empty() && diff.vanished().is_empty());
+        assert!(diff.appeared_sides().is_empty() && diff.vanished_sides().is_empty());
+    }
+}
@@ -8,6 +8,10 @@ pub enum Error {
     BankNotFound(String),
     #[error("currency with name `{0}` not found")]
     CurrencyNotFound(String),
+    #[error("metal with name `{0}` not found")]
+    MetalNotFound(String),
+    #[error("unknown weight unit `{0}`")]
+    WeightUnitNotFound(String),
     #[error("Bank parsing error")]
     BankParseFail,
     #[error("Currency parsing error")]
@@ -16,4 +20,8 @@ pub enum Error {
     Http(#[from] reqwest::Error),
     #[error("JSON Serialization error")]
     JsonSerialization(#[from] serde_json::Error),
+    #[error("invalid snapshot format")]
+    SnapshotFormat,
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
 }
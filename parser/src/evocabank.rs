@@ -1,4 +1,7 @@
-use crate::{BankBody, BankImpl, BankParseFail, Currency, CurrencyBody, CurrencyName, Error};
+use crate::{
+    serialize_categories, trace_rows, BankBody, BankImpl, BankParseFail, Currency, CurrencyBody,
+    CurrencyName, CurrencyType, Error, RateCategories,
+};
 use scraper::{ElementRef, Html, Selector};
 use serde::Serialize;
 use std::str::FromStr;
@@ -9,6 +12,8 @@ pub struct Evocabank {
     body: BankBody,
     cash_currencies: CurrencyBody,
     cashless_currencies: CurrencyBody,
+    #[serde(flatten, serialize_with = "serialize_categories")]
+    other_currencies: RateCategories,
     #[serde(skip_serializing)]
     cash_selector: Selector,
     #[serde(skip_serializing)]
@@ -23,9 +28,18 @@ impl Default for Evocabank {
             body: BankBody {
                 name: "Evocabank",
                 url: "https://www.evoca.am/",
+                legal_name: "Evocabank CJSC",
+                code: "220",
+                swift: "PRMOAM22",
+                website: "https://www.evoca.am/",
+                rates_page: "https://www.evoca.am/",
+                address: "44/2 Arami St, Yerevan 0010",
+                phone: "+374 10 605555",
+                rate_types: &[CurrencyType::Cash, CurrencyType::Noncash],
             },
             cash_currencies: Default::default(),
             cashless_currencies: Default::default(),
+            other_currencies: Default::default(),
             cash_selector: Selector::parse("#tab-1 > div > div.exchange > div > div.exchange__box > div > div > table > tbody > tr").unwrap(),
             no_cash_selector: Selector::parse("#tab-2 > div > div.exchange > div > div.exchange__box > div > div > table > tbody > tr").unwrap(),
             span_selector: Selector::parse("span").unwrap(),
@@ -35,6 +49,8 @@ impl Default for Evocabank {
 
 impl BankImpl for Evocabank {
     fn parse_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.cash_selector, CurrencyType::Cash);
+
         for element in document.select(&self.cash_selector).take(4) {
             let currency_name = element
                 .select(&self.span_selector)
@@ -69,6 +85,8 @@ impl BankImpl for Evocabank {
     }
 
     fn parse_no_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.no_cash_selector, CurrencyType::Noncash);
+
         for element in document.select(&self.no_cash_selector).take(4) {
             let currency_name = element
                 .select(&self.span_selector)
@@ -109,6 +127,26 @@ impl BankImpl for Evocabank {
         &self.cashless_currencies
     }
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cash_currencies
+    }
+
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cashless_currencies
+    }
+
+    fn other_currencies(&self) -> &RateCategories {
+        &self.other_currencies
+    }
+
+    fn other_currencies_mut(&mut self) -> &mut RateCategories {
+        &mut self.other_currencies
+    }
+
+    fn info(&self) -> &BankBody {
+        &self.body
+    }
+
     fn get_name(&self) -> &str {
         self.body.name
     }
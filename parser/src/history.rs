@@ -0,0 +1,189 @@
+use crate::snapshot::Snapshot;
+use crate::validation::Side;
+use crate::Error;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// Snapshots ordered by the time they were taken.
+#[derive(Default, Debug, Clone)]
+pub struct History {
+    snapshots: Vec<Snapshot>,
+}
+
+impl History {
+    /// Loads every `.json` snapshot of a directory, files that are not snapshots are skipped.
+    ///
+    /// Snapshots without fetch times are dated by the file modification time.
+    pub fn load_dir<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut snapshots = Vec::new();
+
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.extension() != Some("json".as_ref()) {
+                continue;
+            }
+
+            let mut snapshot = match Snapshot::from_file(&path) {
+                Ok(snapshot) => snapshot,
+                Err(error) => {
+                    tracing::warn!(path = %path.display(), %error, "not a snapshot, skipped");
+                    continue;
+                }
+            };
+            if snapshot.taken_at().is_none() {
+                let modified: DateTime<Utc> = std::fs::metadata(&path)?.modified()?.into();
+                snapshot.set_taken_at(modified);
+            }
+            snapshots.push(snapshot);
+        }
+
+        Ok(Self::new(snapshots))
+    }
+
+    pub fn new(mut snapshots: Vec<Snapshot>) -> Self {
+        snapshots.sort_by_key(|snapshot| *snapshot.taken_at());
+
+        Self { snapshots }
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    /// Bank names found in any snapshot, in order of first appearance.
+    pub fn banks(&self) -> Vec<&str> {
+        let mut banks: Vec<&str> = Vec::new();
+
+        for bank in self.snapshots.iter().flat_map(|snapshot| snapshot.banks()) {
+            if !banks.contains(&bank.as_str()) {
+                banks.push(bank);
+            }
+        }
+
+        banks
+    }
+
+    /// Rates of one side over time, snapshots taken before `since` or missing the rate are skipped.
+    pub fn series(
+        &self,
+        bank: &str,
+        rate_type: &str,
+        currency: &str,
+        side: Side,
+        since: Option<DateTime<Utc>>,
+    ) -> Vec<(DateTime<Utc>, f64)> {
+        self.snapshots
+            .iter()
+            .filter_map(|snapshot| {
+                let taken_at = (*snapshot.taken_at())?;
+                if since.is_some_and(|since| taken_at < since) {
+                    return None;
+                }
+
+                let quote = snapshot.get(bank, rate_type, currency)?;
+                let rate = match side {
+                    Side::Buy => quote.buy(),
+                    Side::Sell => quote.sell(),
+                };
+
+                Some((taken_at, rate?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(fetched_at: &str, usd: &str) -> Snapshot {
+        Snapshot::from_json(&format!(
+            r#"{{"Idbank": {{"cash_currencies": {{"fetched_at": "{fetched_at}", "usd": {usd}}}}}}}"#
+        ))
+        .unwrap()
+    }
+
+    fn time(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn history() -> History {
+        // Out of order on purpose, the history sorts snapshots by time
+        History::new(vec![
+            snapshot("2024-05-01T12:00:00Z", r#"{"buy": 386.0, "sell": 391.0}"#),
+            snapshot("2024-05-01T10:00:00Z", r#"{"buy": 385.0, "sell": 390.0}"#),
+            snapshot("2024-05-01T11:00:00Z", r#"{"buy": null, "sell": 390.5}"#),
+        ])
+    }
+
+    #[test]
+    fn series_is_ordered_by_time() {
+        let series = history().series("Idbank", "cash", "USD", Side::Sell, None);
+
+        assert_eq!(
+            series,
+            [
+                (time("2024-05-01T10:00:00Z"), 390.0),
+                (time("2024-05-01T11:00:00Z"), 390.5),
+                (time("2024-05-01T12:00:00Z"), 391.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn series_skips_snapshots_without_the_rate() {
+        let history = history();
+
+        let buy = history.series("Idbank", "cash", "usd", Side::Buy, None);
+        assert_eq!(
+            buy,
+            [
+                (time("2024-05-01T10:00:00Z"), 385.0),
+                (time("2024-05-01T12:00:00Z"), 386.0),
+            ]
+        );
+        assert!(history
+            .series("Idbank", "noncash", "USD", Side::Buy, None)
+            .is_empty());
+        assert!(history
+            .series("Unibank", "cash", "USD", Side::Buy, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn series_starts_at_since() {
+        let series = history().series(
+            "Idbank",
+            "cash",
+            "USD",
+            Side::Sell,
+            Some(time("2024-05-01T11:00:00Z")),
+        );
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].0, time("2024-05-01T11:00:00Z"));
+    }
+
+    #[test]
+    fn load_dir_skips_files_that_are_not_snapshots() {
+        let dir = std::env::temp_dir().join(format!("armbankrate-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("snapshot.json"),
+            r#"{"Idbank": {"cash_currencies": {"fetched_at": "2024-05-01T10:00:00Z", "usd": {"buy": 385.0, "sell": 390.0}}}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("export.json"), r#"[{"bank": "Idbank"}]"#).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not json").unwrap();
+
+        let history = History::load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let history = history.unwrap();
+        assert_eq!(history.snapshots().len(), 1);
+        assert_eq!(history.banks(), ["Idbank"]);
+    }
+}
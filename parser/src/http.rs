@@ -0,0 +1,253 @@
+use crate::cache::{self, CacheEntry};
+use crate::{throttle, Error, CLIENT};
+use chrono::{DateTime, Utc};
+use reqwest::header::{
+    HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{RequestBuilder, StatusCode, Url};
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// Request to a bank, its description finds its cache entry.
+#[derive(Debug)]
+pub(crate) enum Request<'a> {
+    Get(&'a str),
+    PostForm(&'a str, &'a [(&'a str, &'a str)]),
+}
+
+impl Request<'_> {
+    fn url(&self) -> &str {
+        match self {
+            Request::Get(url) | Request::PostForm(url, _) => url,
+        }
+    }
+
+    /// Method, URL and form of the request, e.g. `POST https://idbank.am/ RATE_TYPE=CASH`.
+    fn description(&self) -> String {
+        match self {
+            Request::Get(url) => format!("GET {url}"),
+            Request::PostForm(url, form) => {
+                let form: Vec<String> = form
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect();
+                format!("POST {url} {}", form.join("&"))
+            }
+        }
+    }
+
+    fn builder(&self) -> RequestBuilder {
+        match self {
+            Request::Get(url) => CLIENT.get(*url),
+            Request::PostForm(url, form) => CLIENT.post(*url).form(form),
+        }
+    }
+}
+
+/// Response body and the time it was downloaded or last confirmed by the bank.
+pub(crate) struct Fetched {
+    pub(crate) body: String,
+    pub(crate) fetched_at: DateTime<Utc>,
+}
+
+/// Sends `request` for `bank` through the response cache.
+pub(crate) async fn fetch(
+    bank: &str,
+    bank_ttl: Duration,
+    request: Request<'_>,
+) -> Result<Fetched, Error> {
+    let span = tracing::debug_span!("fetch", bank, url = request.url());
+
+    fetch_cached(bank, bank_ttl, request).instrument(span).await
+}
+
+async fn fetch_cached(
+    bank: &str,
+    bank_ttl: Duration,
+    request: Request<'_>,
+) -> Result<Fetched, Error> {
+    let mode = cache::mode();
+    if !mode.is_enabled() {
+        let response = send(&request, None).await?;
+        return Ok(Fetched {
+            body: response.body,
+            fetched_at: Utc::now(),
+        });
+    }
+
+    let ttl = cache::ttl(bank, bank_ttl);
+    let key = cache::key(bank, &request.description());
+    let cached = cache::load(&key);
+
+    if let Some(entry) = &cached {
+        if mode.serves(entry, ttl) {
+            tracing::debug!(age_ms = entry.age().as_millis() as u64, "cache hit");
+            return Ok(Fetched {
+                body: entry.body().to_string(),
+                fetched_at: entry.fetched_at(),
+            });
+        }
+    }
+
+    let response = send(&request, cached.as_ref()).await?;
+    if let (StatusCode::NOT_MODIFIED, Some(mut entry)) = (response.status, cached) {
+        tracing::debug!("not modified, using cached response");
+        entry.touch(ttl);
+        // A cache that cannot be written only costs a request next time
+        let _ = cache::store(&key, &entry);
+
+        return Ok(Fetched {
+            body: entry.body().to_string(),
+            fetched_at: entry.fetched_at(),
+        });
+    }
+
+    let success = response.status.is_success();
+    let (etag, last_modified) = (
+        header(&response.headers, ETAG),
+        header(&response.headers, LAST_MODIFIED),
+    );
+
+    let entry = CacheEntry::new(bank, request.url(), etag, last_modified, ttl, response.body);
+    if success {
+        let _ = cache::store(&key, &entry);
+    }
+
+    Ok(Fetched {
+        fetched_at: entry.fetched_at(),
+        body: entry.body().to_string(),
+    })
+}
+
+struct Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: String,
+}
+
+/// Sends `request` within the throttling limits, retrying 429/503 responses after `Retry-After`.
+///
+/// The validators of the `cached` entry make the request conditional.
+async fn send(request: &Request<'_>, cached: Option<&CacheEntry>) -> Result<Response, Error> {
+    let host = Url::parse(request.url())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut attempt = 0;
+
+    loop {
+        let queued = Instant::now();
+        let _permit = throttle::acquire(&host).await;
+        let start = Instant::now();
+        tracing::trace!(
+            waited_ms = (start - queued).as_millis() as u64,
+            "request slot acquired"
+        );
+
+        let mut builder = request.builder();
+        if let Some(entry) = cached {
+            if let Some(etag) = entry.etag() {
+                builder = builder.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = entry.last_modified() {
+                builder = builder.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = builder.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+
+        if matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        ) {
+            let limits = throttle::limits();
+            if let Some(delay) = retry_after(&headers) {
+                if attempt < limits.max_retries() && delay <= limits.max_retry_after() {
+                    tracing::warn!(
+                        status = status.as_u16(),
+                        retry_after_ms = delay.as_millis() as u64,
+                        attempt,
+                        "rate limited, retrying"
+                    );
+                    throttle::pause(&host, delay);
+                    attempt += 1;
+                    continue;
+                }
+            }
+        }
+
+        let body = response.text().await?;
+        tracing::debug!(
+            status = status.as_u16(),
+            bytes = body.len(),
+            duration_ms = start.elapsed().as_millis() as u64,
+            "response received"
+        );
+
+        return Ok(Response {
+            status,
+            headers,
+            body,
+        });
+    }
+}
+
+/// Delay of a `Retry-After` header, given in seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = header(headers, RETRY_AFTER)?;
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+fn header(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn retry_after_in_seconds() {
+        assert_eq!(retry_after(&headers("120")), Some(Duration::from_secs(120)));
+        assert_eq!(retry_after(&headers(" 5 ")), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        let date = (Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = retry_after(&headers(&date)).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+
+        // A date in the past means the request may be retried right away
+        let past = (Utc::now() - chrono::Duration::seconds(60)).to_rfc2822();
+        assert_eq!(retry_after(&headers(&past)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn invalid_retry_after_is_ignored() {
+        assert_eq!(retry_after(&headers("soon")), None);
+        assert_eq!(retry_after(&headers("-5")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+}
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 
 use crate::{
-    BankBody, BankImpl, BankParseFail, Currency, CurrencyBody, CurrencyName, Error, CLIENT,
+    http, serialize_categories, trace_rows, BankBody, BankImpl, BankParseFail, Currency,
+    CurrencyBody, CurrencyName, CurrencyType, Error, RateCategories,
 };
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
@@ -14,6 +15,8 @@ pub struct Idbank {
     body: BankBody,
     cash_currencies: CurrencyBody,
     cashless_currencies: CurrencyBody,
+    #[serde(flatten, serialize_with = "serialize_categories")]
+    other_currencies: RateCategories,
     #[serde(skip_serializing)]
     main_selector: Selector,
     #[serde(skip_serializing)]
@@ -28,50 +31,71 @@ impl Default for Idbank {
             body: BankBody {
                 name: "Idbank",
                 url: "https://idbank.am/en/rates/",
+                legal_name: "IDBank CJSC",
+                code: "118",
+                swift: "ANIKAM22",
+                website: "https://idbank.am/",
+                rates_page: "https://idbank.am/en/rates/",
+                address: "13 Vazgen Sargsyan St, Yerevan 0010",
+                phone: "+374 10 593333",
+                rate_types: &[CurrencyType::Cash, CurrencyType::Noncash],
             },
             cash_currencies: Default::default(),
             cashless_currencies: Default::default(),
+            other_currencies: Default::default(),
             main_selector: Selector::parse("#\\.default > div.m-exchange > div.m-exchange__table > div > .m-exchange__table-cell:nth-child(1)").unwrap(),
-            currency_name_regex: Regex::new(r"\d \w{3}").unwrap(),
+            currency_name_regex: Regex::new(r"(\d+) (\w{3})").unwrap(),
             currency_value_regex: Regex::new(r"\d+\.?\d+").unwrap(),
         }
     }
 }
 
+impl Idbank {
+    /// Parses a currency name and the number of units it is quoted per, e.g. `100 RUB`.
+    fn parse_currency_name(&self, element: &ElementRef) -> Option<(CurrencyName, u32)> {
+        let inner_html = element.inner_html();
+        let captures = self.currency_name_regex.captures(&inner_html)?;
+
+        let unit = captures[1].parse::<u32>().ok()?;
+        let currency_name = CurrencyName::from_str(&captures[2]).ok()?;
+
+        Some((currency_name, unit))
+    }
+}
+
 #[async_trait]
 impl BankImpl for Idbank {
     async fn parse(&mut self) -> Result<(), Error> {
-        let cash_response = CLIENT
-            .post(self.get_url())
-            .form(&[("RATE_TYPE", "CASH")])
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        let cashless_response = CLIENT
-            .post(self.get_url())
-            .form(&[("RATE_TYPE", "NO_CASH")])
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        self.parse_cash(&Html::parse_document(&cash_response))?;
-        self.parse_no_cash(&Html::parse_document(&cashless_response))?;
+        let cash_response = http::fetch(
+            self.get_name(),
+            self.cache_ttl(),
+            http::Request::PostForm(self.get_url(), &[("RATE_TYPE", "CASH")]),
+        )
+        .await?;
+        let cashless_response = http::fetch(
+            self.get_name(),
+            self.cache_ttl(),
+            http::Request::PostForm(self.get_url(), &[("RATE_TYPE", "NO_CASH")]),
+        )
+        .await?;
+
+        self.parse_cash(&Html::parse_document(&cash_response.body))?;
+        self.parse_no_cash(&Html::parse_document(&cashless_response.body))?;
+
+        self.cash_currencies
+            .set_fetched_at(cash_response.fetched_at);
+        self.cashless_currencies
+            .set_fetched_at(cashless_response.fetched_at);
 
         Ok(())
     }
 
     fn parse_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.main_selector, CurrencyType::Cash);
+
         for element in document.select(&self.main_selector).skip(1).take(5) {
-            let currency_name = match self.currency_name_regex.find(&element.inner_html()) {
-                Some(matched) => {
-                    match CurrencyName::from_str(matched.as_str().trim_start_matches("1 ")) {
-                        Ok(name) => name,
-                        Err(_) => continue,
-                    }
-                }
+            let (currency_name, unit) = match self.parse_currency_name(&element) {
+                Some(parsed) => parsed,
                 None => continue,
             };
 
@@ -97,21 +121,19 @@ impl BankImpl for Idbank {
                 }
             };
 
-            let currency = Currency::new(currency_name, Some(currency_buy), Some(currency_sell));
+            let currency =
+                Currency::with_unit(currency_name, Some(currency_buy), Some(currency_sell), unit);
             self.cash_currencies.fill_from_currency(currency);
         }
         Ok(())
     }
 
     fn parse_no_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.main_selector, CurrencyType::Noncash);
+
         for element in document.select(&self.main_selector).skip(1) {
-            let currency_name = match self.currency_name_regex.find(&element.inner_html()) {
-                Some(matched) => {
-                    match CurrencyName::from_str(matched.as_str().trim_start_matches("1 ")) {
-                        Ok(name) => name,
-                        Err(_) => continue,
-                    }
-                }
+            let (currency_name, unit) = match self.parse_currency_name(&element) {
+                Some(parsed) => parsed,
                 None => continue,
             };
 
@@ -137,7 +159,8 @@ impl BankImpl for Idbank {
                 }
             };
 
-            let currency = Currency::new(currency_name, Some(currency_buy), Some(currency_sell));
+            let currency =
+                Currency::with_unit(currency_name, Some(currency_buy), Some(currency_sell), unit);
             self.cashless_currencies.fill_from_currency(currency);
         }
 
@@ -152,6 +175,26 @@ impl BankImpl for Idbank {
         &self.cashless_currencies
     }
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cash_currencies
+    }
+
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cashless_currencies
+    }
+
+    fn other_currencies(&self) -> &RateCategories {
+        &self.other_currencies
+    }
+
+    fn other_currencies_mut(&mut self) -> &mut RateCategories {
+        &mut self.other_currencies
+    }
+
+    fn info(&self) -> &BankBody {
+        &self.body
+    }
+
     fn get_name(&self) -> &str {
         self.body.name
     }
@@ -1,7 +1,9 @@
 use crate::{
-    BankBody, BankImpl, BankParseFail, Currency, CurrencyBody, CurrencyName, Error, CLIENT,
+    http, serialize_categories, BankBody, BankImpl, BankParseFail, Currency, CurrencyBody,
+    CurrencyName, CurrencyType, Error, RateCategories,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use scraper::Html;
 use serde::Serialize;
 use serde_json::Value;
@@ -14,6 +16,8 @@ pub struct Inecobank {
     body: BankBody,
     cash_currencies: CurrencyBody,
     cashless_currencies: CurrencyBody,
+    #[serde(flatten, serialize_with = "serialize_categories")]
+    other_currencies: RateCategories,
 }
 
 impl Default for Inecobank {
@@ -22,22 +26,25 @@ impl Default for Inecobank {
             body: BankBody {
                 name: "Inecobank",
                 url: "https://www.inecobank.am/api/rates/",
+                legal_name: "Inecobank CJSC",
+                code: "205",
+                swift: "INECAM22",
+                website: "https://www.inecobank.am/",
+                rates_page: "https://www.inecobank.am/",
+                address: "17 Tumanyan St, Yerevan 0001",
+                phone: "+374 10 510510",
+                rate_types: &[CurrencyType::Cash, CurrencyType::Noncash],
             },
             cash_currencies: Default::default(),
             cashless_currencies: Default::default(),
+            other_currencies: Default::default(),
         }
     }
 }
 
-#[async_trait]
-impl BankImpl for Inecobank {
-    async fn parse(&mut self) -> Result<(), Error> {
-        let response = CLIENT
-            .get(self.get_url())
-            .send()
-            .await?
-            .json::<HashMap<String, Value>>()
-            .await?;
+impl Inecobank {
+    fn parse_response(&mut self, body: &str, fetched_at: DateTime<Utc>) -> Result<(), Error> {
+        let response = serde_json::from_str::<HashMap<String, Value>>(body)?;
 
         for item in response["items"].as_array().ok_or(BankParseFail)? {
             let code = item["code"].as_str().ok_or(BankParseFail)?;
@@ -59,15 +66,50 @@ impl BankImpl for Inecobank {
 
             // Fill cashless
             let currency = Currency::new(
-                currency_name,
+                currency_name.clone(),
                 cashless["buy"].as_f64(),
                 cashless["sell"].as_f64(),
             );
             self.cashless_currencies.fill_from_currency(currency);
+
+            // Fill card and transfer, which only some items have
+            for (key, currency_type) in [
+                ("card", CurrencyType::Card),
+                ("transfer", CurrencyType::Transfer),
+            ] {
+                if let Some(rates) = item[key].as_object() {
+                    let currency = Currency::new(
+                        currency_name.clone(),
+                        rates["buy"].as_f64(),
+                        rates["sell"].as_f64(),
+                    );
+                    self.currencies_mut(&currency_type)
+                        .fill_from_currency(currency);
+                }
+            }
+        }
+
+        for currency_type in self.currency_types() {
+            self.currencies_mut(&currency_type)
+                .set_fetched_at(fetched_at);
         }
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl BankImpl for Inecobank {
+    async fn parse(&mut self) -> Result<(), Error> {
+        let response = http::fetch(
+            self.get_name(),
+            self.cache_ttl(),
+            http::Request::Get(self.get_url()),
+        )
+        .await?;
+
+        self.parse_response(&response.body, response.fetched_at)
+    }
 
     fn parse_cash(&mut self, _document: &Html) -> Result<(), Error> {
         unreachable!()
@@ -85,6 +127,26 @@ impl BankImpl for Inecobank {
         &self.cashless_currencies
     }
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cash_currencies
+    }
+
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cashless_currencies
+    }
+
+    fn other_currencies(&self) -> &RateCategories {
+        &self.other_currencies
+    }
+
+    fn other_currencies_mut(&mut self) -> &mut RateCategories {
+        &mut self.other_currencies
+    }
+
+    fn info(&self) -> &BankBody {
+        &self.body
+    }
+
     fn get_name(&self) -> &str {
         self.body.name
     }
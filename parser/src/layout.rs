@@ -0,0 +1,234 @@
+use crate::{Bank, BankImpl, Currency, CurrencyName, CurrencyType};
+
+/// Arrangement of banks and currencies in a rate table.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One row per bank, one column per currency.
+    #[default]
+    BankRows,
+    /// One row per currency, one column per bank.
+    CurrencyRows,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Header<'a> {
+    Bank(&'a Bank),
+    Currency(&'a CurrencyName),
+}
+
+#[derive(Debug)]
+pub struct GridCell<'a> {
+    bank: &'a Bank,
+    currency_name: &'a CurrencyName,
+    currency: &'a Currency,
+}
+
+impl<'a> GridCell<'a> {
+    pub fn bank(&self) -> &'a Bank {
+        self.bank
+    }
+
+    pub fn currency_name(&self) -> &'a CurrencyName {
+        self.currency_name
+    }
+
+    pub fn currency(&self) -> &'a Currency {
+        self.currency
+    }
+}
+
+#[derive(Debug)]
+pub struct GridRow<'a> {
+    header: Header<'a>,
+    cells: Vec<GridCell<'a>>,
+}
+
+impl<'a> GridRow<'a> {
+    pub fn header(&self) -> &Header<'a> {
+        &self.header
+    }
+
+    pub fn cells(&self) -> &[GridCell<'a>] {
+        &self.cells
+    }
+}
+
+/// Rates of one currency type arranged for display, independent of the output format.
+#[derive(Debug)]
+pub struct RateGrid<'a> {
+    layout: Layout,
+    columns: Vec<Header<'a>>,
+    rows: Vec<GridRow<'a>>,
+}
+
+impl<'a> RateGrid<'a> {
+    pub fn new(
+        banks: &[&'a Bank],
+        currency_type: &CurrencyType,
+        currency_names: &'a [CurrencyName],
+        layout: Layout,
+    ) -> Self {
+        let cell = |bank: &'a Bank, currency_name: &'a CurrencyName| GridCell {
+            bank,
+            currency_name,
+            currency: bank.currencies(currency_type).get_rate(currency_name),
+        };
+
+        // A currency asked for twice gets one column or row.
+        let mut unique_names: Vec<&'a CurrencyName> = Vec::with_capacity(currency_names.len());
+        for currency_name in currency_names {
+            if !unique_names.contains(&currency_name) {
+                unique_names.push(currency_name);
+            }
+        }
+
+        let (columns, rows) = match layout {
+            Layout::BankRows => (
+                unique_names
+                    .iter()
+                    .map(|name| Header::Currency(name))
+                    .collect(),
+                banks
+                    .iter()
+                    .map(|bank| GridRow {
+                        header: Header::Bank(bank),
+                        cells: unique_names
+                            .iter()
+                            .map(|currency_name| cell(bank, currency_name))
+                            .collect(),
+                    })
+                    .collect(),
+            ),
+            Layout::CurrencyRows => (
+                banks.iter().map(|bank| Header::Bank(bank)).collect(),
+                unique_names
+                    .iter()
+                    .map(|currency_name| GridRow {
+                        header: Header::Currency(currency_name),
+                        cells: banks.iter().map(|bank| cell(bank, currency_name)).collect(),
+                    })
+                    .collect(),
+            ),
+        };
+
+        Self {
+            layout,
+            columns,
+            rows,
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn columns(&self) -> &[Header<'a>] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[GridRow<'a>] {
+        &self.rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn bank(name: &str, usd: f64, eur: f64) -> Bank {
+        let mut bank = Bank::from_str(name).unwrap();
+        let currencies = bank.cash_currencies_mut();
+        currencies.fill_from_currency(Currency::new(CurrencyName::USD, Some(usd), None));
+        currencies.fill_from_currency(Currency::new(CurrencyName::EUR, Some(eur), None));
+
+        bank
+    }
+
+    fn headers(headers: &[Header]) -> Vec<String> {
+        headers
+            .iter()
+            .map(|header| match header {
+                Header::Bank(bank) => bank.get_name().to_string(),
+                Header::Currency(currency_name) => format!("{currency_name:?}"),
+            })
+            .collect()
+    }
+
+    fn buys(grid: &RateGrid) -> Vec<Vec<Option<f64>>> {
+        grid.rows()
+            .iter()
+            .map(|row| {
+                row.cells()
+                    .iter()
+                    .map(|cell| *cell.currency().buy())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bank_rows() {
+        let (idbank, unibank) = (bank("idbank", 385.0, 420.0), bank("unibank", 386.0, 421.0));
+        let currency_names = [CurrencyName::USD, CurrencyName::EUR];
+        let grid = RateGrid::new(
+            &[&idbank, &unibank],
+            &CurrencyType::Cash,
+            &currency_names,
+            Layout::BankRows,
+        );
+
+        assert_eq!(grid.layout(), Layout::BankRows);
+        assert_eq!(headers(grid.columns()), ["USD", "EUR"]);
+        let rows: Vec<_> = grid.rows().iter().map(|row| *row.header()).collect();
+        assert_eq!(headers(&rows), ["Idbank", "Unibank"]);
+        assert_eq!(
+            buys(&grid),
+            [[Some(385.0), Some(420.0)], [Some(386.0), Some(421.0)]]
+        );
+    }
+
+    #[test]
+    fn currency_rows() {
+        let (idbank, unibank) = (bank("idbank", 385.0, 420.0), bank("unibank", 386.0, 421.0));
+        let currency_names = [CurrencyName::USD, CurrencyName::EUR];
+        let grid = RateGrid::new(
+            &[&idbank, &unibank],
+            &CurrencyType::Cash,
+            &currency_names,
+            Layout::CurrencyRows,
+        );
+
+        assert_eq!(headers(grid.columns()), ["Idbank", "Unibank"]);
+        let rows: Vec<_> = grid.rows().iter().map(|row| *row.header()).collect();
+        assert_eq!(headers(&rows), ["USD", "EUR"]);
+        assert_eq!(
+            buys(&grid),
+            [[Some(385.0), Some(386.0)], [Some(420.0), Some(421.0)]]
+        );
+        let cell = &grid.rows()[1].cells()[0];
+        assert_eq!(
+            (cell.bank().get_name(), cell.currency_name()),
+            ("Idbank", &CurrencyName::EUR)
+        );
+    }
+
+    #[test]
+    fn repeated_currencies_are_shown_once() {
+        let idbank = bank("idbank", 385.0, 420.0);
+        let currency_names = [CurrencyName::USD, CurrencyName::EUR, CurrencyName::USD];
+
+        for layout in [Layout::BankRows, Layout::CurrencyRows] {
+            let grid = RateGrid::new(&[&idbank], &CurrencyType::Cash, &currency_names, layout);
+            let currencies = match layout {
+                Layout::BankRows => headers(grid.columns()),
+                Layout::CurrencyRows => {
+                    let rows: Vec<_> = grid.rows().iter().map(|row| *row.header()).collect();
+                    headers(&rows)
+                }
+            };
+
+            assert_eq!(currencies, ["USD", "EUR"]);
+        }
+    }
+}
@@ -1,37 +1,58 @@
 extern crate core;
 
 mod ardshinbank;
+pub mod best;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod branches;
+pub mod cache;
 mod conversebank;
+pub mod convert;
+pub mod diff;
 mod error;
 mod evocabank;
+pub mod history;
+mod http;
 mod idbank;
 mod inecobank;
+pub mod layout;
+pub mod metals;
+pub mod snapshot;
 pub mod sort;
+pub mod stream;
+pub mod throttle;
 mod unibank;
+pub mod validation;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 
 use crate::ardshinbank::Ardshinbank;
 use crate::conversebank::Conversebank;
 use crate::evocabank::Evocabank;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use enum_dispatch::enum_dispatch;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use once_cell::sync::Lazy;
 use reqwest::Client;
-use scraper::Html;
-use serde::Serialize;
+use scraper::{Html, Selector};
+use serde::{Serialize, Serializer};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
-use crate::error::Error;
+pub use crate::error::Error;
 use crate::Error::BankParseFail;
 
+use crate::branches::Branch;
 use crate::idbank::Idbank;
 use crate::inecobank::Inecobank;
+use crate::metals::MetalQuotes;
 
 use crate::unibank::Unibank;
+use crate::validation::{Validation, Verdict};
 
 static CLIENT: Lazy<Client> = Lazy::new(|| Client::builder().user_agent("Some").build().unwrap());
 
@@ -42,6 +63,7 @@ pub async fn parse<T: ToString>(banks: &[T]) -> Result<Vec<Bank>, Error> {
         .collect::<Result<Vec<Bank>, Error>>()?;
 
     parse_banks(&mut banks).await;
+    validation::validate_banks(&mut banks);
 
     Ok(banks)
 }
@@ -50,6 +72,7 @@ pub async fn parse_all() -> Result<Vec<Bank>, Error> {
     let mut banks: Vec<Bank> = get_bank_vec();
 
     parse_banks(&mut banks).await;
+    validation::validate_banks(&mut banks);
 
     Ok(banks)
 }
@@ -61,6 +84,7 @@ pub async fn parse_json<T: ToString>(banks: &[T]) -> Result<String, Error> {
         .collect::<Result<Vec<Bank>, Error>>()?;
 
     parse_banks(&mut banks).await;
+    validation::validate_banks(&mut banks);
 
     json_from(&banks)
 }
@@ -69,19 +93,52 @@ pub async fn parse_all_json() -> Result<String, Error> {
     let mut banks = get_bank_vec();
 
     parse_banks(&mut banks).await;
+    validation::validate_banks(&mut banks);
 
     json_from(&banks)
 }
 
-async fn parse_banks(banks: &mut Vec<Bank>) {
+async fn parse_banks(banks: &mut [Bank]) {
     let futures = FuturesUnordered::new();
     for bank in banks {
-        futures.push(bank.parse());
+        futures.push(parse_bank(bank));
     }
     futures.collect::<Vec<Result<_, _>>>().await;
 }
 
-fn json_from(banks: &Vec<Bank>) -> Result<String, Error> {
+/// Parses a bank within a `bank` span, reporting the duration and how many currencies were found.
+async fn parse_bank(bank: &mut Bank) -> Result<(), Error> {
+    let span = tracing::info_span!("bank", bank = bank.get_name());
+    let start = Instant::now();
+
+    let result = bank.parse().instrument(span.clone()).await;
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let _entered = span.enter();
+    match &result {
+        Ok(()) => tracing::info!(
+            duration_ms,
+            cash_currencies = bank.cash_currencies().parsed_count(),
+            noncash_currencies = bank.no_cash_currencies().parsed_count(),
+            metal_quotes = bank.metals().quotes().len(),
+            "bank parsed"
+        ),
+        Err(error) => tracing::warn!(duration_ms, %error, "bank failed"),
+    }
+
+    result
+}
+
+/// Reports how many elements of a bank page matched `selector`.
+pub(crate) fn trace_rows(document: &Html, selector: &Selector, currency_type: CurrencyType) {
+    tracing::debug!(
+        rows = document.select(selector).count(),
+        ?currency_type,
+        "rows matched"
+    );
+}
+
+fn json_from(banks: &[Bank]) -> Result<String, Error> {
     let mut bank_map: HashMap<&str, &Bank> = HashMap::with_capacity(banks.len());
 
     for bank in banks {
@@ -92,6 +149,15 @@ fn json_from(banks: &Vec<Bank>) -> Result<String, Error> {
     Ok(serde_json::to_string(&bank_map)?)
 }
 
+impl FromStr for Bank {
+    type Err = Error;
+
+    /// Bank without rates, by case insensitive name.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        bank_from_str(name)
+    }
+}
+
 fn bank_from_str<T: ToString>(s: T) -> Result<Bank, Error> {
     let s = s.to_string().to_lowercase();
 
@@ -121,14 +187,22 @@ fn get_bank_vec() -> Vec<Bank> {
 #[enum_dispatch]
 pub trait BankImpl: Send {
     async fn parse(&mut self) -> Result<(), Error> {
-        let url = self.get_url();
-
-        let response = CLIENT.get(url).send().await?.text().await?;
-        let document = Html::parse_document(&response);
+        let response = http::fetch(
+            self.get_name(),
+            self.cache_ttl(),
+            http::Request::Get(self.get_url()),
+        )
+        .await?;
+        let document = Html::parse_document(&response.body);
 
         self.parse_cash(&document)?;
         self.parse_no_cash(&document)?;
 
+        self.cash_currencies_mut()
+            .set_fetched_at(response.fetched_at);
+        self.no_cash_currencies_mut()
+            .set_fetched_at(response.fetched_at);
+
         Ok(())
     }
 
@@ -138,26 +212,220 @@ pub trait BankImpl: Send {
     fn cash_currencies(&self) -> &CurrencyBody;
     fn no_cash_currencies(&self) -> &CurrencyBody;
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody;
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody;
+
+    /// Rate categories beyond cash and non-cash that the bank publishes, e.g. card rates.
+    fn other_currencies(&self) -> &RateCategories;
+    fn other_currencies_mut(&mut self) -> &mut RateCategories;
+
+    /// Cash and non-cash followed by the other categories of the bank.
+    fn currency_types(&self) -> Vec<CurrencyType> {
+        let mut currency_types = vec![CurrencyType::Cash, CurrencyType::Noncash];
+        currency_types.extend(self.other_currencies().keys().cloned());
+        currency_types
+    }
+
+    fn has_currencies(&self, currency_type: &CurrencyType) -> bool {
+        match currency_type {
+            CurrencyType::Cash | CurrencyType::Noncash => true,
+            other => self.other_currencies().contains_key(other),
+        }
+    }
+
+    /// Rates of the category, empty when the bank does not publish it.
+    fn currencies(&self, currency_type: &CurrencyType) -> &CurrencyBody {
+        match currency_type {
+            CurrencyType::Cash => self.cash_currencies(),
+            CurrencyType::Noncash => self.no_cash_currencies(),
+            other => self
+                .other_currencies()
+                .get(other)
+                .unwrap_or(&EMPTY_CURRENCIES),
+        }
+    }
+
+    /// Rates of the category, which is added to the bank if it was missing.
+    fn currencies_mut(&mut self, currency_type: &CurrencyType) -> &mut CurrencyBody {
+        match currency_type {
+            CurrencyType::Cash => self.cash_currencies_mut(),
+            CurrencyType::Noncash => self.no_cash_currencies_mut(),
+            other => self
+                .other_currencies_mut()
+                .entry(other.clone())
+                .or_default(),
+        }
+    }
+
+    /// Precious metal quotes, empty when the bank does not publish them.
+    fn metals(&self) -> &MetalQuotes {
+        &EMPTY_METALS
+    }
+
+    /// Metadata of the bank.
+    fn info(&self) -> &BankBody;
+
+    /// Address of the branch list, `None` when the bank does not publish one.
+    fn branches_url(&self) -> Option<&str> {
+        None
+    }
+
+    /// Fetches and parses the branch list, which is not part of [`parse`](Self::parse).
+    async fn parse_branches(&mut self) -> Result<(), Error> {
+        let url = match self.branches_url() {
+            Some(url) => url.to_string(),
+            None => return Ok(()),
+        };
+        let response =
+            http::fetch(self.get_name(), self.cache_ttl(), http::Request::Get(&url)).await?;
+
+        self.parse_branch_list(&response.body)
+    }
+
+    fn parse_branch_list(&mut self, _body: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Branches of the bank, empty when the bank does not publish them or they were not parsed.
+    fn branches(&self) -> &[Branch] {
+        &[]
+    }
+
+    fn branches_mut(&mut self) -> &mut [Branch] {
+        &mut []
+    }
+
     fn get_name(&self) -> &str;
     fn get_url(&self) -> &str;
+
+    /// How long a cached response of the bank stays fresh.
+    fn cache_ttl(&self) -> Duration {
+        cache::DEFAULT_TTL
+    }
 }
 
-#[derive(Default, Debug, Serialize)]
+/// Buy and sell rates of a currency normalized to a single unit.
+///
+/// Banks may publish some rates per 10 or 100 units, the published
+/// values and unit are kept alongside the normalized ones.
+#[derive(Debug, Serialize)]
 pub struct Currency {
     name: CurrencyName,
     buy: Option<f64>,
     sell: Option<f64>,
+    unit: u32,
+    quoted_buy: Option<f64>,
+    quoted_sell: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tiers: Vec<RateTier>,
+    validation: Validation,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self::new(Default::default(), None, None)
+    }
 }
 
-#[derive(Default, Debug)]
-struct BankBody {
+/// Name, contacts and identifiers of a bank, along with the rate categories it publishes.
+#[derive(Default, Debug, Serialize)]
+pub struct BankBody {
     name: &'static str,
+    /// Address the rates are fetched from, a page or an API endpoint.
     url: &'static str,
+    legal_name: &'static str,
+    /// Central Bank of Armenia code, the first digits of the bank's account numbers.
+    code: &'static str,
+    swift: &'static str,
+    website: &'static str,
+    /// Page where the bank shows its rates to visitors.
+    rates_page: &'static str,
+    address: &'static str,
+    phone: &'static str,
+    rate_types: &'static [CurrencyType],
+}
+
+impl BankBody {
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn url(&self) -> &str {
+        self.url
+    }
+
+    pub fn legal_name(&self) -> &str {
+        self.legal_name
+    }
+
+    pub fn code(&self) -> &str {
+        self.code
+    }
+
+    pub fn swift(&self) -> &str {
+        self.swift
+    }
+
+    pub fn website(&self) -> &str {
+        self.website
+    }
+
+    pub fn rates_page(&self) -> &str {
+        self.rates_page
+    }
+
+    pub fn address(&self) -> &str {
+        self.address
+    }
+
+    pub fn phone(&self) -> &str {
+        self.phone
+    }
+
+    /// Rate categories the bank's source may publish, a parse tells which ones it currently does.
+    pub fn rate_types(&self) -> &[CurrencyType] {
+        self.rate_types
+    }
 }
 
 impl Currency {
     pub fn new(name: CurrencyName, buy: Option<f64>, sell: Option<f64>) -> Self {
-        Self { name, buy, sell }
+        Self::with_unit(name, buy, sell, 1)
+    }
+
+    /// Creates a currency from rates published per `unit` units.
+    pub fn with_unit(name: CurrencyName, buy: Option<f64>, sell: Option<f64>, unit: u32) -> Self {
+        let unit = unit.max(1);
+
+        Self {
+            name,
+            buy: buy.map(|buy| buy / unit as f64),
+            sell: sell.map(|sell| sell / unit as f64),
+            unit,
+            quoted_buy: buy,
+            quoted_sell: sell,
+            tiers: Vec::new(),
+            validation: Default::default(),
+        }
+    }
+
+    /// Adds rates the bank applies from larger amounts, quoted per the same unit as the base rates.
+    ///
+    /// No provider parses amount tiers yet, they are added once a bank's tier layout is captured.
+    pub fn with_tiers(mut self, tiers: Vec<RateTier>) -> Self {
+        let unit = self.unit as f64;
+
+        self.tiers = tiers
+            .into_iter()
+            .map(|tier| RateTier {
+                buy: tier.buy.map(|buy| buy / unit),
+                sell: tier.sell.map(|sell| sell / unit),
+                ..tier
+            })
+            .collect();
+        self.tiers
+            .sort_by(|a, b| a.min_amount.total_cmp(&b.min_amount));
+        self
     }
 
     pub fn buy(&self) -> &Option<f64> {
@@ -167,22 +435,99 @@ impl Currency {
     pub fn sell(&self) -> &Option<f64> {
         &self.sell
     }
+
+    pub fn unit(&self) -> u32 {
+        self.unit
+    }
+
+    pub fn quoted_buy(&self) -> &Option<f64> {
+        &self.quoted_buy
+    }
+
+    pub fn quoted_sell(&self) -> &Option<f64> {
+        &self.quoted_sell
+    }
+
+    pub fn validation(&self) -> &Validation {
+        &self.validation
+    }
+
+    /// Amount tiers ordered by their minimum amount, empty when the bank has a single rate.
+    pub fn tiers(&self) -> &[RateTier] {
+        &self.tiers
+    }
+
+    /// Tier applying to `amount` units of the currency, the one with the largest minimum not above it.
+    ///
+    /// Rejected tiers are passed over, the amount falls back to a lower tier or the base rate.
+    pub fn tier_for(&self, amount: f64) -> Option<&RateTier> {
+        self.tiers.iter().rev().find(|tier| {
+            tier.min_amount <= amount && tier.validation.verdict() != &Verdict::Rejected
+        })
+    }
+
+    /// Buy rate for `amount` units, the base rate when no tier applies.
+    pub fn buy_for(&self, amount: f64) -> &Option<f64> {
+        match self.tier_for(amount) {
+            Some(tier) if tier.buy.is_some() => &tier.buy,
+            _ => &self.buy,
+        }
+    }
+
+    /// Sell rate for `amount` units, the base rate when no tier applies.
+    pub fn sell_for(&self, amount: f64) -> &Option<f64> {
+        match self.tier_for(amount) {
+            Some(tier) if tier.sell.is_some() => &tier.sell,
+            _ => &self.sell,
+        }
+    }
 }
 
+/// Rates a bank applies to transactions of at least `min_amount` units of a currency.
 #[derive(Debug, Clone, Serialize)]
+pub struct RateTier {
+    min_amount: f64,
+    buy: Option<f64>,
+    sell: Option<f64>,
+    validation: Validation,
+}
+
+impl RateTier {
+    pub fn new(min_amount: f64, buy: Option<f64>, sell: Option<f64>) -> Self {
+        Self {
+            min_amount,
+            buy,
+            sell,
+            validation: Default::default(),
+        }
+    }
+
+    pub fn min_amount(&self) -> f64 {
+        self.min_amount
+    }
+
+    pub fn buy(&self) -> &Option<f64> {
+        &self.buy
+    }
+
+    pub fn sell(&self) -> &Option<f64> {
+        &self.sell
+    }
+
+    pub fn validation(&self) -> &Validation {
+        &self.validation
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub enum CurrencyName {
     USD,
     GBP,
     EUR,
+    #[default]
     RUB,
 }
 
-impl Default for CurrencyName {
-    fn default() -> Self {
-        Self::RUB
-    }
-}
-
 impl FromStr for CurrencyName {
     type Err = error::Error;
 
@@ -205,9 +550,18 @@ pub struct CurrencyBody {
     gbp: Currency,
     eur: Currency,
     rub: Currency,
+    fetched_at: Option<DateTime<Utc>>,
 }
 
 impl CurrencyBody {
+    /// Number of currencies with at least one rate.
+    pub fn parsed_count(&self) -> usize {
+        [&self.usd, &self.gbp, &self.eur, &self.rub]
+            .iter()
+            .filter(|currency| currency.buy.is_some() || currency.sell.is_some())
+            .count()
+    }
+
     pub fn get_usd_rate(&self) -> &Currency {
         &self.usd
     }
@@ -224,6 +578,44 @@ impl CurrencyBody {
         &self.rub
     }
 
+    pub fn get_rate(&self, currency_name: &CurrencyName) -> &Currency {
+        match currency_name {
+            CurrencyName::USD => &self.usd,
+            CurrencyName::GBP => &self.gbp,
+            CurrencyName::EUR => &self.eur,
+            CurrencyName::RUB => &self.rub,
+        }
+    }
+
+    pub fn get_rate_mut(&mut self, currency_name: &CurrencyName) -> &mut Currency {
+        match currency_name {
+            CurrencyName::USD => &mut self.usd,
+            CurrencyName::GBP => &mut self.gbp,
+            CurrencyName::EUR => &mut self.eur,
+            CurrencyName::RUB => &mut self.rub,
+        }
+    }
+
+    /// Time the rates were downloaded from the bank.
+    pub fn fetched_at(&self) -> &Option<DateTime<Utc>> {
+        &self.fetched_at
+    }
+
+    pub fn set_fetched_at(&mut self, fetched_at: DateTime<Utc>) {
+        self.fetched_at = Some(fetched_at);
+    }
+
+    /// Time since the rates were downloaded.
+    pub fn age(&self) -> Option<Duration> {
+        let time = self.fetched_at?;
+
+        Utc::now().signed_duration_since(time).to_std().ok()
+    }
+
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.age().is_some_and(|age| age > max_age)
+    }
+
     pub fn fill_from_currency(&mut self, currency: Currency) {
         match currency.name {
             CurrencyName::USD => self.usd = currency,
@@ -246,8 +638,156 @@ pub enum Bank {
     Unibank,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 pub enum CurrencyType {
     Cash,
     Noncash,
+    Card,
+    Transfer,
+}
+
+impl CurrencyType {
+    pub const ALL: [CurrencyType; 4] = [
+        CurrencyType::Cash,
+        CurrencyType::Noncash,
+        CurrencyType::Card,
+        CurrencyType::Transfer,
+    ];
+
+    /// Name of the category in snapshots, e.g. `noncash`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            CurrencyType::Cash => "cash",
+            CurrencyType::Noncash => "noncash",
+            CurrencyType::Card => "card",
+            CurrencyType::Transfer => "transfer",
+        }
+    }
+}
+
+/// Rates of the categories a bank publishes besides cash and non-cash.
+pub type RateCategories = BTreeMap<CurrencyType, CurrencyBody>;
+
+static EMPTY_CURRENCIES: Lazy<CurrencyBody> = Lazy::new(CurrencyBody::default);
+static EMPTY_METALS: Lazy<MetalQuotes> = Lazy::new(MetalQuotes::default);
+
+/// Serializes the categories next to the cash bodies, e.g. as `card_currencies`.
+pub(crate) fn serialize_categories<S: Serializer>(
+    categories: &RateCategories,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(categories.iter().map(|(currency_type, currencies)| {
+        (format!("{}_currencies", currency_type.key()), currencies)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// USD cash quoted per 10 units, with better rates from 1000 and 5000 units.
+    fn tiered() -> Currency {
+        Currency::with_unit(CurrencyName::USD, Some(3850.0), Some(3900.0), 10).with_tiers(vec![
+            RateTier::new(5000.0, Some(3870.0), None),
+            RateTier::new(1000.0, Some(3860.0), Some(3890.0)),
+        ])
+    }
+
+    #[test]
+    fn rates_are_divided_by_the_unit() {
+        let currency = Currency::with_unit(CurrencyName::RUB, Some(480.0), Some(520.0), 100);
+
+        assert_eq!(currency.buy(), &Some(4.8));
+        assert_eq!(currency.sell(), &Some(5.2));
+        assert_eq!(currency.unit(), 100);
+        assert_eq!(currency.quoted_buy(), &Some(480.0));
+        assert_eq!(currency.quoted_sell(), &Some(520.0));
+    }
+
+    #[test]
+    fn zero_unit_is_treated_as_one() {
+        let currency = Currency::with_unit(CurrencyName::USD, Some(386.0), None, 0);
+
+        assert_eq!(currency.unit(), 1);
+        assert_eq!(currency.buy(), &Some(386.0));
+        assert_eq!(currency.sell(), &None);
+    }
+
+    #[test]
+    fn tiers_are_normalized_and_ordered() {
+        let currency = tiered();
+        let tiers: Vec<_> = currency
+            .tiers()
+            .iter()
+            .map(|tier| (tier.min_amount(), *tier.buy(), *tier.sell()))
+            .collect();
+
+        assert_eq!(
+            tiers,
+            vec![
+                (1000.0, Some(386.0), Some(389.0)),
+                (5000.0, Some(387.0), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn amount_below_first_tier_uses_base_rates() {
+        let currency = tiered();
+
+        assert!(currency.tier_for(999.99).is_none());
+        assert_eq!(currency.buy_for(999.99), &Some(385.0));
+        assert_eq!(currency.sell_for(999.99), &Some(390.0));
+    }
+
+    #[test]
+    fn amount_at_tier_minimum_uses_that_tier() {
+        let currency = tiered();
+
+        assert_eq!(
+            currency.tier_for(1000.0).map(RateTier::min_amount),
+            Some(1000.0)
+        );
+        assert_eq!(currency.buy_for(1000.0), &Some(386.0));
+        assert_eq!(currency.sell_for(1000.0), &Some(389.0));
+        assert_eq!(
+            currency.tier_for(5000.0).map(RateTier::min_amount),
+            Some(5000.0)
+        );
+        assert_eq!(currency.buy_for(5000.0), &Some(387.0));
+    }
+
+    #[test]
+    fn side_missing_from_tier_falls_back_to_base_rate() {
+        assert_eq!(tiered().sell_for(10_000.0), &Some(390.0));
+    }
+
+    #[test]
+    fn age_is_counted_from_the_fetch_time() {
+        let mut body = CurrencyBody::default();
+        assert_eq!(body.age(), None);
+        assert!(!body.is_stale(Duration::ZERO));
+
+        body.set_fetched_at(Utc::now() - chrono::Duration::minutes(5));
+        let age = body.age().unwrap();
+        assert!(age >= Duration::from_secs(300) && age < Duration::from_secs(360));
+    }
+
+    #[test]
+    fn stale_when_older_than_max_age() {
+        let mut body = CurrencyBody::default();
+        body.set_fetched_at(Utc::now() - chrono::Duration::minutes(30));
+
+        assert!(body.is_stale(Duration::from_secs(60 * 10)));
+        assert!(!body.is_stale(Duration::from_secs(60 * 60)));
+    }
+
+    #[test]
+    fn future_times_are_not_stale() {
+        let mut body = CurrencyBody::default();
+        body.set_fetched_at(Utc::now() + chrono::Duration::hours(1));
+
+        assert_eq!(body.age(), None);
+        assert!(!body.is_stale(Duration::ZERO));
+    }
 }
@@ -0,0 +1,179 @@
+//! Precious metal bullion quotes, kept apart from currency rates.
+//!
+//! No bank provides metal quotes yet, a provider gets them once a real response listing
+//! bullion prices is captured as a test fixture.
+
+use crate::{Bank, BankImpl, Error};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+const GRAMS_PER_TROY_OUNCE: f64 = 31.103_476_8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum Metal {
+    Gold,
+    Silver,
+}
+
+impl FromStr for Metal {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_uppercase();
+
+        match value.as_str() {
+            "XAU" | "GOLD" => Ok(Metal::Gold),
+            "XAG" | "SILVER" => Ok(Metal::Silver),
+            _ => Err(Error::MetalNotFound(value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightUnit {
+    Gram,
+    TroyOunce,
+}
+
+impl WeightUnit {
+    pub fn grams(&self) -> f64 {
+        match self {
+            WeightUnit::Gram => 1.0,
+            WeightUnit::TroyOunce => GRAMS_PER_TROY_OUNCE,
+        }
+    }
+
+    /// Short symbol, e.g. `g`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            WeightUnit::Gram => "g",
+            WeightUnit::TroyOunce => "oz t",
+        }
+    }
+}
+
+impl FromStr for WeightUnit {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+
+        match value.as_str() {
+            "g" | "gr" | "gram" | "grams" => Ok(WeightUnit::Gram),
+            "oz" | "ozt" | "oz t" | "troy_ounce" | "troy ounce" => Ok(WeightUnit::TroyOunce),
+            _ => Err(Error::WeightUnitNotFound(value)),
+        }
+    }
+}
+
+/// Buy and sell prices, in AMD, of a bullion bar of the given weight and purity.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetalQuote {
+    metal: Metal,
+    weight: f64,
+    unit: WeightUnit,
+    purity: Option<f64>,
+    buy: Option<f64>,
+    sell: Option<f64>,
+}
+
+impl MetalQuote {
+    /// `purity` is the millesimal fineness, e.g. 999.9.
+    pub fn new(
+        metal: Metal,
+        weight: f64,
+        unit: WeightUnit,
+        purity: Option<f64>,
+        buy: Option<f64>,
+        sell: Option<f64>,
+    ) -> Self {
+        Self {
+            metal,
+            weight,
+            unit,
+            purity,
+            buy,
+            sell,
+        }
+    }
+
+    pub fn metal(&self) -> &Metal {
+        &self.metal
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn unit(&self) -> WeightUnit {
+        self.unit
+    }
+
+    pub fn purity(&self) -> Option<f64> {
+        self.purity
+    }
+
+    pub fn buy(&self) -> &Option<f64> {
+        &self.buy
+    }
+
+    pub fn sell(&self) -> &Option<f64> {
+        &self.sell
+    }
+
+    pub fn buy_per_gram(&self) -> Option<f64> {
+        self.per_gram(self.buy)
+    }
+
+    pub fn sell_per_gram(&self) -> Option<f64> {
+        self.per_gram(self.sell)
+    }
+
+    fn per_gram(&self, price: Option<f64>) -> Option<f64> {
+        let grams = self.weight * self.unit.grams();
+        price.filter(|_| grams > 0.0).map(|price| price / grams)
+    }
+}
+
+/// Metal quotes published by a bank.
+#[derive(Default, Debug, Serialize)]
+pub struct MetalQuotes {
+    quotes: Vec<MetalQuote>,
+    fetched_at: Option<DateTime<Utc>>,
+}
+
+impl MetalQuotes {
+    pub fn quotes(&self) -> &[MetalQuote] {
+        &self.quotes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+
+    pub fn push(&mut self, quote: MetalQuote) {
+        self.quotes.push(quote);
+    }
+
+    pub fn fetched_at(&self) -> &Option<DateTime<Utc>> {
+        &self.fetched_at
+    }
+
+    pub fn set_fetched_at(&mut self, fetched_at: DateTime<Utc>) {
+        self.fetched_at = Some(fetched_at);
+    }
+}
+
+/// Metal quotes of the banks that publish them, by bank name.
+pub fn metals_json(banks: &[Bank]) -> Result<String, Error> {
+    let metals: BTreeMap<&str, &MetalQuotes> = banks
+        .iter()
+        .filter(|bank| !bank.metals().is_empty())
+        .map(|bank| (bank.get_name(), bank.metals()))
+        .collect();
+
+    Ok(serde_json::to_string(&metals)?)
+}
@@ -0,0 +1,225 @@
+use crate::{json_from, Bank, Error};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Identifies a quote by bank, rate type (`cash`, `noncash`) and currency (`USD`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct QuoteKey {
+    bank: String,
+    rate_type: String,
+    currency: String,
+}
+
+impl QuoteKey {
+    pub fn bank(&self) -> &str {
+        &self.bank
+    }
+
+    pub fn rate_type(&self) -> &str {
+        &self.rate_type
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Quote {
+    buy: Option<f64>,
+    sell: Option<f64>,
+}
+
+impl Quote {
+    pub fn buy(&self) -> Option<f64> {
+        self.buy
+    }
+
+    pub fn sell(&self) -> Option<f64> {
+        self.sell
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buy.is_none() && self.sell.is_none()
+    }
+}
+
+/// Rates of several banks at one point in time, as archived from the JSON output.
+#[derive(Default, Debug, Clone)]
+pub struct Snapshot {
+    banks: Vec<String>,
+    quotes: BTreeMap<QuoteKey, Quote>,
+    taken_at: Option<DateTime<Utc>>,
+}
+
+impl Snapshot {
+    /// Reads a snapshot from the output of `parse_json`/`parse_all_json`.
+    ///
+    /// Older outputs without units, validation or timestamps are accepted as well.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: Value = serde_json::from_str(json)?;
+
+        Self::from_value(&value)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_banks(banks: &[Bank]) -> Result<Self, Error> {
+        Self::from_json(&json_from(banks)?)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        let mut snapshot = Snapshot::default();
+
+        for (bank_name, bank) in value.as_object().ok_or(Error::SnapshotFormat)? {
+            snapshot.banks.push(bank_name.clone());
+
+            for (key, currencies) in bank.as_object().ok_or(Error::SnapshotFormat)? {
+                let rate_type = match rate_type_from_key(key) {
+                    Some(rate_type) => rate_type,
+                    None => continue,
+                };
+                let currencies = currencies.as_object().ok_or(Error::SnapshotFormat)?;
+
+                snapshot.read_time(currencies);
+                for (currency, quote) in currencies {
+                    let quote = match quote.as_object() {
+                        Some(quote) => quote,
+                        None => continue,
+                    };
+
+                    snapshot.quotes.insert(
+                        QuoteKey {
+                            bank: bank_name.clone(),
+                            rate_type: rate_type.to_string(),
+                            currency: currency.to_uppercase(),
+                        },
+                        Quote {
+                            buy: quote.get("buy").and_then(Value::as_f64),
+                            sell: quote.get("sell").and_then(Value::as_f64),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Uses the latest fetch time of all rates as the time the snapshot was taken.
+    fn read_time(&mut self, currencies: &Map<String, Value>) {
+        let fetched_at = currencies
+            .get("fetched_at")
+            .and_then(Value::as_str)
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc));
+
+        if fetched_at > self.taken_at {
+            self.taken_at = fetched_at;
+        }
+    }
+
+    pub fn banks(&self) -> &[String] {
+        &self.banks
+    }
+
+    pub fn quotes(&self) -> &BTreeMap<QuoteKey, Quote> {
+        &self.quotes
+    }
+
+    pub fn get(&self, bank: &str, rate_type: &str, currency: &str) -> Option<&Quote> {
+        self.quotes.get(&QuoteKey {
+            bank: bank.to_string(),
+            rate_type: rate_type.to_string(),
+            currency: currency.to_uppercase(),
+        })
+    }
+
+    pub fn taken_at(&self) -> &Option<DateTime<Utc>> {
+        &self.taken_at
+    }
+
+    pub fn set_taken_at(&mut self, taken_at: DateTime<Utc>) {
+        self.taken_at = Some(taken_at);
+    }
+}
+
+/// Maps JSON body keys to rate types, banks name the non-cash body differently.
+fn rate_type_from_key(key: &str) -> Option<&'static str> {
+    match key {
+        "cash_currencies" => Some("cash"),
+        "cashless_currencies" | "no_cash_currencies" => Some("noncash"),
+        "card_currencies" => Some("card"),
+        "transfer_currencies" => Some("transfer"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn quotes_are_read_from_every_rate_body() {
+        let snapshot = Snapshot::from_value(&json!({
+            "Ardshinbank": {
+                "cash_currencies": {"usd": {"buy": 385.0, "sell": 391.0}},
+                "no_cash_currencies": {"usd": {"buy": 386.0, "sell": 390.0}}
+            },
+            "Inecobank": {
+                "cashless_currencies": {"eur": {"buy": 418.5, "sell": null}},
+                "card_currencies": {"rub": {"buy": 4.7, "sell": 5.1}}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(snapshot.banks(), ["Ardshinbank", "Inecobank"]);
+        assert_eq!(snapshot.quotes().len(), 4);
+
+        let quote = snapshot.get("Ardshinbank", "noncash", "usd").unwrap();
+        assert_eq!((quote.buy(), quote.sell()), (Some(386.0), Some(390.0)));
+        let quote = snapshot.get("Inecobank", "noncash", "EUR").unwrap();
+        assert_eq!((quote.buy(), quote.sell()), (Some(418.5), None));
+        assert!(snapshot.get("Inecobank", "card", "RUB").is_some());
+        assert!(snapshot.taken_at().is_none());
+    }
+
+    #[test]
+    fn the_latest_fetch_time_dates_the_snapshot() {
+        let snapshot = Snapshot::from_value(&json!({
+            "Idbank": {
+                "cash_currencies": {"fetched_at": "2024-05-01T10:00:00Z"},
+                "no_cash_currencies": {"fetched_at": "2024-05-01T10:05:00+00:00"}
+            },
+            "Unibank": {"cash_currencies": {"fetched_at": "not a time"}}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            snapshot.taken_at().unwrap().to_rfc3339(),
+            "2024-05-01T10:05:00+00:00"
+        );
+        // Fields that are not quotes are skipped
+        assert!(snapshot.quotes().is_empty());
+    }
+
+    #[test]
+    fn other_json_is_not_a_snapshot() {
+        for value in [
+            json!([1, 2]),
+            json!({"Idbank": 1}),
+            json!({"Idbank": {"cash_currencies": []}}),
+        ] {
+            assert!(matches!(
+                Snapshot::from_value(&value),
+                Err(Error::SnapshotFormat)
+            ));
+        }
+    }
+}
@@ -1,64 +1,128 @@
-use crate::{Bank, BankImpl, Currency, CurrencyBody, CurrencyName, CurrencyType};
+use crate::validation::{median, Side};
+use crate::{Bank, BankImpl, Currency, CurrencyName, CurrencyType};
 use std::cmp::Ordering;
 
+/// Sorts banks by a single key.
 pub fn sort_banks(banks: &mut [Bank], sort_data: &SortData) {
-    banks.sort_by(|a, b| {
-        let (a_currencies, b_currencies): (&CurrencyBody, &CurrencyBody) =
-            currencies_by_type(a, b, &sort_data.currency_type);
-
-        match sort_data.currency_name {
-            CurrencyName::USD => compare(
-                a_currencies.get_usd_rate(),
-                b_currencies.get_usd_rate(),
-                &sort_data.order_type,
-            ),
-            CurrencyName::GBP => compare(
-                a_currencies.get_gbp_rate(),
-                b_currencies.get_gbp_rate(),
-                &sort_data.order_type,
-            ),
-            CurrencyName::EUR => compare(
-                a_currencies.get_eur_rate(),
-                b_currencies.get_eur_rate(),
-                &sort_data.order_type,
-            ),
-            CurrencyName::RUB => compare(
-                a_currencies.get_rub_rate(),
-                b_currencies.get_rub_rate(),
-                &sort_data.order_type,
-            ),
+    sort_banks_by(banks, std::slice::from_ref(sort_data));
+}
+
+/// Sorts banks by several keys, later keys break ties of the earlier ones.
+pub fn sort_banks_by(banks: &mut [Bank], sort_data: &[SortData]) {
+    if sort_data.is_empty() {
+        return;
+    }
+
+    // Values are computed once per bank, keyed by position so banks sharing a name stay apart
+    let mut values = vec![vec![None; sort_data.len()]; banks.len()];
+    for (index, data) in sort_data.iter().enumerate() {
+        let medians = deviation_median(banks, data);
+
+        for (bank, bank_values) in banks.iter().zip(values.iter_mut()) {
+            let currency = bank
+                .currencies(&data.currency_type)
+                .get_rate(&data.currency_name);
+            bank_values[index] = sort_value(currency, data, medians);
         }
-        .reverse()
+    }
+
+    let mut order: Vec<usize> = (0..banks.len()).collect();
+    order.sort_by(|a, b| {
+        sort_data
+            .iter()
+            .enumerate()
+            .map(|(index, data)| compare(values[*a][index], values[*b][index], data))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
     });
-}
 
-fn currencies_by_type<'a>(
-    a: &'a Bank,
-    b: &'a Bank,
-    currency_type: &CurrencyType,
-) -> (&'a CurrencyBody, &'a CurrencyBody) {
-    match currency_type {
-        CurrencyType::Cash => (a.cash_currencies(), b.cash_currencies()),
-        CurrencyType::Noncash => (a.no_cash_currencies(), b.no_cash_currencies()),
+    // Moves the bank at `order[position]` to `position`, following the ones already swapped away
+    for position in 0..order.len() {
+        let mut source = order[position];
+        while source < position {
+            source = order[source];
+        }
+        banks.swap(position, source);
     }
 }
 
-fn compare(a: &Currency, b: &Currency, order_type: &OrderType) -> Ordering {
-    let (a, b) = match order_type {
-        OrderType::Buy => (a.buy(), b.buy()),
-        OrderType::Sell => (a.sell(), b.sell()),
+/// Cross-bank median of the side the deviation is computed for.
+fn deviation_median(banks: &[Bank], sort_data: &SortData) -> Option<f64> {
+    let side = match sort_data.order_type {
+        OrderType::Deviation(side) => side,
+        _ => return None,
+    };
+
+    let values = banks
+        .iter()
+        .map(|bank| {
+            bank.currencies(&sort_data.currency_type)
+                .get_rate(&sort_data.currency_name)
+        })
+        .filter_map(|currency| side_value(currency, side, sort_data.amount))
+        .collect();
+
+    median(values)
+}
+
+/// Rate of a side, of the tier matching `amount` when one is given.
+fn side_value(currency: &Currency, side: Side, amount: Option<f64>) -> Option<f64> {
+    let value = match (side, amount) {
+        (Side::Buy, None) => *currency.buy(),
+        (Side::Sell, None) => *currency.sell(),
+        (Side::Buy, Some(amount)) => *currency.buy_for(amount),
+        (Side::Sell, Some(amount)) => *currency.sell_for(amount),
     };
 
-    a.unwrap_or_default().total_cmp(&b.unwrap_or_default())
+    value.filter(|value| *value > 0.0)
+}
+
+fn sort_value(currency: &Currency, sort_data: &SortData, median: Option<f64>) -> Option<f64> {
+    let amount = sort_data.amount;
+
+    match sort_data.order_type {
+        OrderType::Buy => side_value(currency, Side::Buy, amount),
+        OrderType::Sell => side_value(currency, Side::Sell, amount),
+        OrderType::Spread => Some(
+            side_value(currency, Side::Sell, amount)? - side_value(currency, Side::Buy, amount)?,
+        ),
+        OrderType::Deviation(side) => {
+            let median = median?;
+            Some((side_value(currency, side, amount)? - median) / median)
+        }
+    }
+}
+
+fn compare(a: Option<f64>, b: Option<f64>, sort_data: &SortData) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match sort_data.direction {
+            Direction::Ascending => a.total_cmp(&b),
+            Direction::Descending => b.total_cmp(&a),
+        },
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => match sort_data.missing {
+            Missing::First => Ordering::Less,
+            Missing::Last => Ordering::Greater,
+        },
+        (Some(_), None) => match sort_data.missing {
+            Missing::First => Ordering::Greater,
+            Missing::Last => Ordering::Less,
+        },
+    }
 }
 
+#[derive(Debug, Clone)]
 pub struct SortData {
     currency_type: CurrencyType,
     currency_name: CurrencyName,
     order_type: OrderType,
+    direction: Direction,
+    missing: Missing,
+    amount: Option<f64>,
 }
 
 impl SortData {
+    /// Sorts in descending order with missing rates placed last.
     pub fn new(
         currency_type: CurrencyType,
         currency_name: CurrencyName,
@@ -68,11 +132,285 @@ impl SortData {
             currency_type,
             currency_name,
             order_type,
+            direction: Direction::Descending,
+            missing: Missing::Last,
+            amount: None,
         }
     }
+
+    /// Sorts the best rate for a customer first: the highest buy when selling to the bank,
+    /// the lowest sell when buying from it and the narrowest spread.
+    pub fn best_for_customer(
+        currency_type: CurrencyType,
+        currency_name: CurrencyName,
+        order_type: OrderType,
+    ) -> Self {
+        let direction = match order_type {
+            OrderType::Buy | OrderType::Deviation(Side::Buy) => Direction::Descending,
+            OrderType::Sell | OrderType::Spread | OrderType::Deviation(Side::Sell) => {
+                Direction::Ascending
+            }
+        };
+
+        Self::new(currency_type, currency_name, order_type).with_direction(direction)
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_missing(mut self, missing: Missing) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    /// Compares the rates of the tiers matching a transaction of `amount` units.
+    pub fn with_amount(mut self, amount: f64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OrderType {
     Buy,
     Sell,
+    /// Difference between the sell and buy rates.
+    Spread,
+    /// Relative deviation of a side from the cross-bank median.
+    Deviation(Side),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+/// Placement of banks that have no rate for the sort key.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Missing {
+    First,
+    #[default]
+    Last,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// Bank with the given cash buy and sell rates of USD and EUR.
+    fn bank(name: &str, usd: (Option<f64>, Option<f64>), eur: (Option<f64>, Option<f64>)) -> Bank {
+        let mut bank = Bank::from_str(name).unwrap();
+        let currencies = bank.cash_currencies_mut();
+        currencies.fill_from_currency(Currency::new(CurrencyName::USD, usd.0, usd.1));
+        currencies.fill_from_currency(Currency::new(CurrencyName::EUR, eur.0, eur.1));
+
+        bank
+    }
+
+    fn banks() -> Vec<Bank> {
+        vec![
+            bank(
+                "Idbank",
+                (Some(385.0), Some(391.0)),
+                (Some(418.0), Some(428.0)),
+            ),
+            bank(
+                "Unibank",
+                (Some(387.0), Some(392.0)),
+                (Some(417.0), Some(427.0)),
+            ),
+            bank("Evocabank", (None, None), (Some(419.0), Some(426.0))),
+            bank(
+                "Inecobank",
+                (Some(385.0), Some(389.0)),
+                (Some(416.0), Some(425.0)),
+            ),
+        ]
+    }
+
+    fn names(banks: &[Bank]) -> Vec<&str> {
+        banks.iter().map(|bank| bank.get_name()).collect()
+    }
+
+    fn usd(order_type: OrderType) -> SortData {
+        SortData::new(CurrencyType::Cash, CurrencyName::USD, order_type)
+    }
+
+    #[test]
+    fn sorts_descending_with_missing_rates_last() {
+        let mut banks = banks();
+
+        sort_banks(&mut banks, &usd(OrderType::Sell));
+
+        assert_eq!(
+            names(&banks),
+            ["Unibank", "Idbank", "Inecobank", "Evocabank"]
+        );
+    }
+
+    #[test]
+    fn missing_rates_can_go_first() {
+        let mut banks = banks();
+
+        sort_banks(
+            &mut banks,
+            &usd(OrderType::Sell)
+                .with_direction(Direction::Ascending)
+                .with_missing(Missing::First),
+        );
+
+        assert_eq!(
+            names(&banks),
+            ["Evocabank", "Inecobank", "Idbank", "Unibank"]
+        );
+    }
+
+    #[test]
+    fn later_keys_break_ties() {
+        let mut banks = banks();
+        let eur_sell = SortData::new(CurrencyType::Cash, CurrencyName::EUR, OrderType::Sell)
+            .with_direction(Direction::Ascending);
+
+        // Idbank and Inecobank buy USD at 385, Inecobank sells EUR cheaper
+        sort_banks_by(&mut banks, &[usd(OrderType::Buy), eur_sell.clone()]);
+        assert_eq!(
+            names(&banks),
+            ["Unibank", "Inecobank", "Idbank", "Evocabank"]
+        );
+
+        sort_banks_by(
+            &mut banks,
+            &[
+                usd(OrderType::Buy),
+                eur_sell.with_direction(Direction::Descending),
+            ],
+        );
+        assert_eq!(
+            names(&banks),
+            ["Unibank", "Idbank", "Inecobank", "Evocabank"]
+        );
+    }
+
+    #[test]
+    fn spread_is_sell_minus_buy() {
+        let mut banks = banks();
+
+        sort_banks(
+            &mut banks,
+            &SortData::best_for_customer(CurrencyType::Cash, CurrencyName::USD, OrderType::Spread),
+        );
+
+        // Spreads are 4, 5 and 6, Evocabank has none
+        assert_eq!(
+            names(&banks),
+            ["Inecobank", "Unibank", "Idbank", "Evocabank"]
+        );
+    }
+
+    #[test]
+    fn deviation_is_measured_from_the_median_of_the_quoting_banks() {
+        let banks = banks();
+
+        // Banks without the rate do not count: the median of 385, 385 and 387 is 385
+        let data = usd(OrderType::Deviation(Side::Buy));
+        assert_eq!(deviation_median(&banks, &data), Some(385.0));
+        // 389, 391 and 392
+        let data = usd(OrderType::Deviation(Side::Sell));
+        assert_eq!(deviation_median(&banks, &data), Some(391.0));
+        // 425, 426, 427 and 428
+        let data = SortData::new(
+            CurrencyType::Cash,
+            CurrencyName::EUR,
+            OrderType::Deviation(Side::Sell),
+        );
+        assert_eq!(deviation_median(&banks, &data), Some(426.5));
+
+        assert_eq!(deviation_median(&banks, &usd(OrderType::Buy)), None);
+    }
+
+    #[test]
+    fn sorts_by_deviation() {
+        let mut banks = banks();
+
+        sort_banks(&mut banks, &usd(OrderType::Deviation(Side::Sell)));
+
+        assert_eq!(
+            names(&banks),
+            ["Unibank", "Idbank", "Inecobank", "Evocabank"]
+        );
+        let currency = banks[0].cash_currencies().get_rate(&CurrencyName::USD);
+        let deviation = sort_value(
+            currency,
+            &usd(OrderType::Deviation(Side::Sell)),
+            Some(391.0),
+        );
+        assert_eq!(deviation, Some(1.0 / 391.0));
+    }
+
+    #[test]
+    fn best_for_customer_picks_the_direction_of_each_order_type() {
+        let direction = |order_type| {
+            SortData::best_for_customer(CurrencyType::Cash, CurrencyName::USD, order_type).direction
+        };
+
+        assert_eq!(direction(OrderType::Buy), Direction::Descending);
+        assert_eq!(direction(OrderType::Sell), Direction::Ascending);
+        assert_eq!(direction(OrderType::Spread), Direction::Ascending);
+        assert_eq!(
+            direction(OrderType::Deviation(Side::Buy)),
+            Direction::Descending
+        );
+        assert_eq!(
+            direction(OrderType::Deviation(Side::Sell)),
+            Direction::Ascending
+        );
+    }
+
+    #[test]
+    fn compare_orders_missing_values() {
+        let last = usd(OrderType::Buy);
+        let first = usd(OrderType::Buy).with_missing(Missing::First);
+
+        assert_eq!(compare(None, Some(1.0), &last), Ordering::Greater);
+        assert_eq!(compare(Some(1.0), None, &last), Ordering::Less);
+        assert_eq!(compare(None, Some(1.0), &first), Ordering::Less);
+        assert_eq!(compare(Some(1.0), None, &first), Ordering::Greater);
+        assert_eq!(compare(None, None, &first), Ordering::Equal);
+    }
+
+    #[test]
+    fn no_sort_keys_keep_the_order() {
+        let mut banks = banks();
+
+        sort_banks_by(&mut banks, &[]);
+
+        assert_eq!(
+            names(&banks),
+            ["Idbank", "Unibank", "Evocabank", "Inecobank"]
+        );
+    }
+
+    #[test]
+    fn banks_sharing_a_name_keep_their_own_rates() {
+        let rates = [389.0, 384.0, 388.0, 386.0, 390.0, 385.0];
+        let mut banks: Vec<Bank> = rates
+            .iter()
+            .map(|buy| bank("Idbank", (Some(*buy), Some(buy + 5.0)), (None, None)))
+            .collect();
+
+        sort_banks(
+            &mut banks,
+            &usd(OrderType::Buy).with_direction(Direction::Ascending),
+        );
+
+        let sorted: Vec<Option<f64>> = banks
+            .iter()
+            .map(|bank| *bank.cash_currencies().get_rate(&CurrencyName::USD).buy())
+            .collect();
+        assert_eq!(sorted, [384.0, 385.0, 386.0, 388.0, 389.0, 390.0].map(Some));
+    }
 }
@@ -0,0 +1,106 @@
+use crate::validation::validate_banks;
+use crate::{bank_from_str, get_bank_vec, parse_bank, Bank, BankBody, BankImpl, Error};
+use futures::stream::{BoxStream, FuturesUnordered};
+use futures::StreamExt;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Result of parsing a single bank, yielded by [`parse_stream`] as soon as the bank finishes.
+#[derive(Debug)]
+pub struct BankOutcome {
+    bank: Bank,
+    error: Option<Error>,
+    elapsed: Duration,
+}
+
+impl BankOutcome {
+    /// The bank with whatever rates were parsed before an error occurred.
+    pub fn bank(&self) -> &Bank {
+        &self.bank
+    }
+
+    pub fn into_bank(self) -> Bank {
+        self.bank
+    }
+
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Time spent fetching and parsing the bank.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Single line JSON object, suitable for newline delimited JSON output.
+    pub fn to_json(&self) -> Result<String, Error> {
+        self.json(false)
+    }
+
+    /// JSON object like [`to_json`](Self::to_json), with the metadata of the bank.
+    pub fn to_detail_json(&self) -> Result<String, Error> {
+        self.json(true)
+    }
+
+    fn json(&self, with_info: bool) -> Result<String, Error> {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            bank: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            info: Option<&'a BankBody>,
+            elapsed_ms: u128,
+            error: Option<String>,
+            rates: &'a Bank,
+        }
+
+        Ok(serde_json::to_string(&Line {
+            bank: self.bank.get_name(),
+            info: with_info.then(|| self.bank.info()),
+            elapsed_ms: self.elapsed.as_millis(),
+            error: self.error.as_ref().map(ToString::to_string),
+            rates: &self.bank,
+        })?)
+    }
+}
+
+/// Parses the given banks concurrently, yielding each one as soon as it finishes.
+///
+/// Every bank is validated on its own, comparisons with other banks need
+/// [`validate_banks`] on the collected banks.
+pub fn parse_stream<T: ToString>(banks: &[T]) -> Result<BoxStream<'static, BankOutcome>, Error> {
+    let banks = banks
+        .iter()
+        .map(|bank| bank_from_str(bank.to_string()))
+        .collect::<Result<Vec<Bank>, Error>>()?;
+
+    Ok(stream_banks(banks))
+}
+
+/// Parses all banks concurrently, yielding each one as soon as it finishes.
+pub fn parse_all_stream() -> BoxStream<'static, BankOutcome> {
+    stream_banks(get_bank_vec())
+}
+
+fn stream_banks(banks: Vec<Bank>) -> BoxStream<'static, BankOutcome> {
+    let futures = FuturesUnordered::new();
+    for mut bank in banks {
+        futures.push(async move {
+            let start = Instant::now();
+            let error = parse_bank(&mut bank).await.err();
+            let elapsed = start.elapsed();
+            validate_banks(std::slice::from_mut(&mut bank));
+
+            BankOutcome {
+                bank,
+                error,
+                elapsed,
+            }
+        });
+    }
+
+    futures.boxed()
+}
@@ -0,0 +1,257 @@
+//! Limits applied to every request sent to the banks.
+//!
+//! A global limit caps the requests in flight, per host requests are spaced by a minimum
+//! interval and an optional rate limit, and `Retry-After` of 429/503 responses pauses the host.
+
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+static LIMITS: Lazy<RwLock<Limits>> = Lazy::new(|| RwLock::new(Limits::default()));
+static SEMAPHORE: Lazy<RwLock<Arc<Semaphore>>> =
+    Lazy::new(|| RwLock::new(Arc::new(Semaphore::new(Limits::default().max_concurrency))));
+static HOSTS: Lazy<Mutex<HashMap<String, HostState>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone)]
+pub struct Limits {
+    max_concurrency: usize,
+    min_interval: Duration,
+    rate_limit: Option<(u32, Duration)>,
+    max_retries: u32,
+    max_retry_after: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 6,
+            min_interval: Duration::from_millis(500),
+            rate_limit: None,
+            max_retries: 2,
+            max_retry_after: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Limits {
+    /// Requests in flight across all hosts, at least one.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Time between the starts of two requests to the same host.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// At most `requests` to the same host within `period`.
+    pub fn with_rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.rate_limit = Some((requests.max(1), period));
+        self
+    }
+
+    /// Retries of 429/503 responses, a `Retry-After` longer than `max_retry_after` is not waited for.
+    pub fn with_retries(mut self, max_retries: u32, max_retry_after: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    pub fn rate_limit(&self) -> Option<(u32, Duration)> {
+        self.rate_limit
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub fn max_retry_after(&self) -> Duration {
+        self.max_retry_after
+    }
+}
+
+/// Replaces the limits, requests already waiting keep the previous concurrency limit.
+pub fn set_limits(limits: Limits) {
+    *SEMAPHORE.write().unwrap() = Arc::new(Semaphore::new(limits.max_concurrency));
+    *LIMITS.write().unwrap() = limits;
+}
+
+pub fn limits() -> Limits {
+    LIMITS.read().unwrap().clone()
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    started: VecDeque<Instant>,
+    paused_until: Option<Instant>,
+}
+
+impl HostState {
+    /// Time to wait before the next request may start, zero when it may start now.
+    fn wait(&self, limits: &Limits, now: Instant) -> Duration {
+        let mut ready = now;
+
+        if let Some(last) = self.started.back() {
+            ready = ready.max(*last + limits.min_interval);
+        }
+        if let Some((requests, period)) = limits.rate_limit {
+            if self.started.len() >= requests as usize {
+                ready = ready.max(self.started[self.started.len() - requests as usize] + period);
+            }
+        }
+        if let Some(paused_until) = self.paused_until {
+            ready = ready.max(paused_until);
+        }
+
+        ready - now
+    }
+
+    fn record(&mut self, limits: &Limits, now: Instant) {
+        self.started.push_back(now);
+
+        let keep = limits
+            .rate_limit
+            .map_or(1, |(requests, _)| requests as usize)
+            .max(1);
+        while self.started.len() > keep {
+            self.started.pop_front();
+        }
+    }
+}
+
+/// Waits for the host's turn and a free slot, the request may run while the permit is held.
+///
+/// The permit is only taken once the host is ready, so a throttled host does not hold a slot
+/// other hosts could use while it sleeps.
+pub(crate) async fn acquire(host: &str) -> OwnedSemaphorePermit {
+    loop {
+        let wait = turn(host, false);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+            continue;
+        }
+
+        let semaphore = SEMAPHORE.read().unwrap().clone();
+        let permit = semaphore
+            .acquire_owned()
+            .await
+            .expect("the semaphore is never closed");
+
+        // Another request to the host may have started while waiting for the permit
+        if turn(host, true).is_zero() {
+            return permit;
+        }
+    }
+}
+
+/// Time until the host's turn, records a request start when `start` is set and it is now.
+fn turn(host: &str, start: bool) -> Duration {
+    let limits = limits();
+    let mut hosts = HOSTS.lock().unwrap();
+    let state = hosts.entry(host.to_string()).or_default();
+    let now = Instant::now();
+
+    let wait = state.wait(&limits, now);
+    if start && wait.is_zero() {
+        state.record(&limits, now);
+    }
+    wait
+}
+
+/// Holds back requests to `host` for `delay`, e.g. as asked by `Retry-After`.
+pub(crate) fn pause(host: &str, delay: Duration) {
+    let until = Instant::now() + delay;
+    let mut hosts = HOSTS.lock().unwrap();
+    let state = hosts.entry(host.to_string()).or_default();
+
+    state.paused_until = Some(state.paused_until.map_or(until, |paused| paused.max(until)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_request_does_not_wait() {
+        let state = HostState::default();
+
+        assert!(state.wait(&Limits::default(), Instant::now()).is_zero());
+    }
+
+    #[test]
+    fn requests_are_spaced_by_min_interval() {
+        let limits = Limits::default().with_min_interval(Duration::from_millis(500));
+        let mut state = HostState::default();
+        let now = Instant::now();
+
+        state.record(&limits, now);
+
+        assert_eq!(state.wait(&limits, now), Duration::from_millis(500));
+        assert_eq!(
+            state.wait(&limits, now + Duration::from_millis(200)),
+            Duration::from_millis(300)
+        );
+        assert!(state.wait(&limits, now + Duration::from_secs(1)).is_zero());
+    }
+
+    #[test]
+    fn rate_limit_waits_for_the_oldest_request_in_the_window() {
+        let limits = Limits::default()
+            .with_min_interval(Duration::ZERO)
+            .with_rate_limit(2, Duration::from_secs(10));
+        let mut state = HostState::default();
+        let now = Instant::now();
+
+        state.record(&limits, now);
+        assert!(state.wait(&limits, now + Duration::from_secs(1)).is_zero());
+        state.record(&limits, now + Duration::from_secs(1));
+
+        assert_eq!(
+            state.wait(&limits, now + Duration::from_secs(2)),
+            Duration::from_secs(8)
+        );
+    }
+
+    #[test]
+    fn record_keeps_only_the_rate_limit_window() {
+        let limits = Limits::default().with_rate_limit(2, Duration::from_secs(10));
+        let mut state = HostState::default();
+        let now = Instant::now();
+
+        for second in 0..5 {
+            state.record(&limits, now + Duration::from_secs(second));
+        }
+        assert_eq!(state.started.len(), 2);
+
+        let mut state = HostState::default();
+        for second in 0..5 {
+            state.record(&Limits::default(), now + Duration::from_secs(second));
+        }
+        assert_eq!(state.started.len(), 1);
+    }
+
+    #[test]
+    fn pause_holds_back_the_host() {
+        let limits = Limits::default().with_min_interval(Duration::ZERO);
+        let now = Instant::now();
+        let state = HostState {
+            paused_until: Some(now + Duration::from_secs(3)),
+            ..Default::default()
+        };
+
+        assert_eq!(state.wait(&limits, now), Duration::from_secs(3));
+        assert!(state.wait(&limits, now + Duration::from_secs(3)).is_zero());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{BankBody, BankImpl, BankParseFail, Currency, CurrencyBody, CurrencyName, Error};
+use crate::{
+    serialize_categories, trace_rows, BankBody, BankImpl, BankParseFail, Currency, CurrencyBody,
+    CurrencyName, CurrencyType, Error, RateCategories,
+};
 use scraper::{ElementRef, Html, Selector};
 use serde::Serialize;
 use std::str::FromStr;
@@ -9,6 +12,8 @@ pub struct Unibank {
     body: BankBody,
     cash_currencies: CurrencyBody,
     cashless_currencies: CurrencyBody,
+    #[serde(flatten, serialize_with = "serialize_categories")]
+    other_currencies: RateCategories,
     #[serde(skip_serializing)]
     cash_selector: Selector,
     #[serde(skip_serializing)]
@@ -21,9 +26,18 @@ impl Default for Unibank {
             body: BankBody {
                 name: "Unibank",
                 url: "https://www.unibank.am/",
+                legal_name: "Unibank OJSC",
+                code: "241",
+                swift: "UNIJAM22",
+                website: "https://www.unibank.am/",
+                rates_page: "https://www.unibank.am/",
+                address: "12 Chardakhlyan St, Yerevan 0010",
+                phone: "+374 10 592259",
+                rate_types: &[CurrencyType::Cash, CurrencyType::Noncash],
             },
             cash_currencies: Default::default(),
             cashless_currencies: Default::default(),
+            other_currencies: Default::default(),
             cash_selector: Selector::parse(
                 "#Cash > div.pane__body > ul:nth-child(2) > li:nth-child(3n+1)",
             )
@@ -38,6 +52,8 @@ impl Default for Unibank {
 
 impl BankImpl for Unibank {
     fn parse_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.cash_selector, CurrencyType::Cash);
+
         for element in document.select(&self.cash_selector).take(4) {
             let currency_name = {
                 let value = ElementRef::wrap(element.children().next().ok_or(BankParseFail)?)
@@ -85,6 +101,8 @@ impl BankImpl for Unibank {
     }
 
     fn parse_no_cash(&mut self, document: &Html) -> Result<(), Error> {
+        trace_rows(document, &self.no_cash_selector, CurrencyType::Noncash);
+
         for element in document.select(&self.no_cash_selector) {
             let currency_name = {
                 let value = ElementRef::wrap(element.children().next().ok_or(BankParseFail)?)
@@ -139,6 +157,26 @@ impl BankImpl for Unibank {
         &self.cashless_currencies
     }
 
+    fn cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cash_currencies
+    }
+
+    fn no_cash_currencies_mut(&mut self) -> &mut CurrencyBody {
+        &mut self.cashless_currencies
+    }
+
+    fn other_currencies(&self) -> &RateCategories {
+        &self.other_currencies
+    }
+
+    fn other_currencies_mut(&mut self) -> &mut RateCategories {
+        &mut self.other_currencies
+    }
+
+    fn info(&self) -> &BankBody {
+        &self.body
+    }
+
     fn get_name(&self) -> &str {
         self.body.name
     }
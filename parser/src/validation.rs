@@ -0,0 +1,503 @@
+use crate::{Bank, BankImpl, Currency, CurrencyName, CurrencyType};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+const CURRENCY_NAMES: [CurrencyName; 4] = [
+    CurrencyName::USD,
+    CurrencyName::GBP,
+    CurrencyName::EUR,
+    CurrencyName::RUB,
+];
+
+/// Unit factors that banks commonly quote in (per 10 or per 100 units).
+const UNIT_FACTORS: [f64; 2] = [10.0, 100.0];
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Verdict {
+    #[default]
+    Valid,
+    Suspicious,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Reason {
+    /// The bank buys the currency for more than it sells it.
+    BuyAboveSell,
+    /// The value is zero or negative, usually a placeholder for a missing quote.
+    NonPositive(Side),
+    /// The value deviates from the reference (cross-bank median or given rate) by more than the band.
+    OutOfBand {
+        side: Side,
+        reference: f64,
+        deviation: f64,
+    },
+    /// The value looks like it was quoted per `factor` units instead of per 1 unit.
+    UnitMismatch { side: Side, factor: u32 },
+}
+
+impl Reason {
+    fn verdict(&self) -> Verdict {
+        match self {
+            Reason::BuyAboveSell | Reason::NonPositive(_) => Verdict::Rejected,
+            Reason::OutOfBand { .. } | Reason::UnitMismatch { .. } => Verdict::Suspicious,
+        }
+    }
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::BuyAboveSell => write!(f, "buy rate is above sell rate"),
+            Reason::NonPositive(side) => write!(f, "{side:?} rate is not positive"),
+            Reason::OutOfBand {
+                side,
+                reference,
+                deviation,
+            } => write!(
+                f,
+                "{side:?} rate deviates {:.2}% from reference {reference}",
+                deviation * 100.0
+            ),
+            Reason::UnitMismatch { side, factor } => {
+                write!(f, "{side:?} rate looks quoted per {factor} units")
+            }
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct Validation {
+    verdict: Verdict,
+    reasons: Vec<Reason>,
+}
+
+impl Validation {
+    pub fn verdict(&self) -> &Verdict {
+        &self.verdict
+    }
+
+    pub fn reasons(&self) -> &[Reason] {
+        &self.reasons
+    }
+
+    fn push(&mut self, reason: Reason) {
+        self.verdict = self.verdict.max(reason.verdict());
+        self.reasons.push(reason);
+    }
+}
+
+/// Rules used to validate parsed rates.
+///
+/// By default every quote is compared against the cross-bank median,
+/// explicit reference rates take precedence over the median.
+#[derive(Debug, Clone)]
+pub struct Validator {
+    band: f64,
+    references: HashMap<(CurrencyName, CurrencyType), f64>,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            band: 0.05,
+            references: HashMap::new(),
+        }
+    }
+}
+
+impl Validator {
+    pub fn new(band: f64) -> Self {
+        Self {
+            band,
+            ..Default::default()
+        }
+    }
+
+    /// Uses `rate` instead of the cross-bank median as the reference for the currency.
+    pub fn with_reference(
+        mut self,
+        currency_name: CurrencyName,
+        currency_type: CurrencyType,
+        rate: f64,
+    ) -> Self {
+        self.references.insert((currency_name, currency_type), rate);
+        self
+    }
+
+    pub fn validate(&self, banks: &mut [Bank]) {
+        for currency_type in CurrencyType::ALL {
+            for currency_name in CURRENCY_NAMES {
+                let buy_reference =
+                    self.reference(banks, &currency_name, &currency_type, Side::Buy);
+                let sell_reference =
+                    self.reference(banks, &currency_name, &currency_type, Side::Sell);
+
+                for bank in banks
+                    .iter_mut()
+                    .filter(|bank| bank.has_currencies(&currency_type))
+                {
+                    let currency = bank
+                        .currencies_mut(&currency_type)
+                        .get_rate_mut(&currency_name);
+                    self.validate_currency(currency, buy_reference, sell_reference);
+
+                    // Branches only publish cash rates, held to the bank-wide references
+                    if currency_type != CurrencyType::Cash {
+                        continue;
+                    }
+                    for currencies in bank
+                        .branches_mut()
+                        .iter_mut()
+                        .filter_map(|branch| branch.cash_currencies_mut())
+                    {
+                        let currency = currencies.get_rate_mut(&currency_name);
+                        self.validate_currency(currency, buy_reference, sell_reference);
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_currency(
+        &self,
+        currency: &mut Currency,
+        buy_reference: Option<f64>,
+        sell_reference: Option<f64>,
+    ) {
+        currency.validation =
+            self.check(currency.buy, currency.sell, buy_reference, sell_reference);
+        // Tier rates are held to the same references as the base rates
+        for tier in currency.tiers.iter_mut() {
+            tier.validation = self.check(tier.buy, tier.sell, buy_reference, sell_reference);
+        }
+    }
+
+    fn check(
+        &self,
+        buy: Option<f64>,
+        sell: Option<f64>,
+        buy_reference: Option<f64>,
+        sell_reference: Option<f64>,
+    ) -> Validation {
+        let mut validation = Validation::default();
+
+        if let (Some(buy), Some(sell)) = (buy, sell) {
+            if buy > sell {
+                validation.push(Reason::BuyAboveSell);
+            }
+        }
+        self.check_side(&mut validation, buy, buy_reference, Side::Buy);
+        self.check_side(&mut validation, sell, sell_reference, Side::Sell);
+
+        validation
+    }
+
+    fn check_side(
+        &self,
+        validation: &mut Validation,
+        value: Option<f64>,
+        reference: Option<f64>,
+        side: Side,
+    ) {
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+
+        if value <= 0.0 {
+            validation.push(Reason::NonPositive(side));
+            return;
+        }
+
+        let reference = match reference {
+            Some(reference) => reference,
+            None => return,
+        };
+
+        let deviation = (value - reference).abs() / reference;
+        if deviation <= self.band {
+            return;
+        }
+
+        let unit_mismatch = UNIT_FACTORS.iter().find(|factor| {
+            ((value / *factor - reference).abs() / reference) <= self.band
+                || ((value * *factor - reference).abs() / reference) <= self.band
+        });
+
+        match unit_mismatch {
+            Some(factor) => validation.push(Reason::UnitMismatch {
+                side,
+                factor: *factor as u32,
+            }),
+            None => validation.push(Reason::OutOfBand {
+                side,
+                reference,
+                deviation,
+            }),
+        }
+    }
+
+    fn reference(
+        &self,
+        banks: &[Bank],
+        currency_name: &CurrencyName,
+        currency_type: &CurrencyType,
+        side: Side,
+    ) -> Option<f64> {
+        if let Some(rate) = self
+            .references
+            .get(&(currency_name.clone(), currency_type.clone()))
+        {
+            return Some(*rate);
+        }
+
+        let values = banks
+            .iter()
+            .map(|bank| bank.currencies(currency_type).get_rate(currency_name))
+            .filter_map(|currency| side_value(currency, side))
+            .filter(|value| *value > 0.0)
+            .collect();
+
+        median(values)
+    }
+}
+
+/// Validates every quote of the given banks with the default rules.
+pub fn validate_banks(banks: &mut [Bank]) {
+    Validator::default().validate(banks);
+}
+
+pub(crate) fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+
+    match values.len() % 2 {
+        0 => Some((values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(values[middle]),
+    }
+}
+
+fn side_value(currency: &Currency, side: Side) -> Option<f64> {
+    match side {
+        Side::Buy => currency.buy,
+        Side::Sell => currency.sell,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RateTier;
+    use std::str::FromStr;
+
+    /// Bank with the given cash USD quote.
+    fn bank(name: &str, currency: Currency) -> Bank {
+        let mut bank = Bank::from_str(name).unwrap();
+        bank.cash_currencies_mut().fill_from_currency(currency);
+
+        bank
+    }
+
+    fn usd(bank: &Bank) -> &Currency {
+        bank.currencies(&CurrencyType::Cash)
+            .get_rate(&CurrencyName::USD)
+    }
+
+    fn usd_quote(buy: f64, sell: f64) -> Currency {
+        Currency::new(CurrencyName::USD, Some(buy), Some(sell))
+    }
+
+    fn reasons(bank: &Bank) -> &[Reason] {
+        usd(bank).validation().reasons()
+    }
+
+    fn check(value: f64, reference: f64) -> Validation {
+        let mut validation = Validation::default();
+        Validator::default().check_side(&mut validation, Some(value), Some(reference), Side::Buy);
+
+        validation
+    }
+
+    #[test]
+    fn value_within_band_is_valid() {
+        let validation = check(390.0, 387.0);
+
+        assert_eq!(validation.verdict(), &Verdict::Valid);
+        assert!(validation.reasons().is_empty());
+    }
+
+    #[test]
+    fn non_positive_value_is_rejected() {
+        for value in [0.0, -1.0] {
+            let validation = check(value, 387.0);
+
+            assert_eq!(validation.verdict(), &Verdict::Rejected);
+            assert_eq!(validation.reasons(), [Reason::NonPositive(Side::Buy)]);
+        }
+    }
+
+    #[test]
+    fn value_quoted_per_more_units_is_suspicious() {
+        let validation = check(38700.0, 387.0);
+
+        assert_eq!(validation.verdict(), &Verdict::Suspicious);
+        assert_eq!(
+            validation.reasons(),
+            [Reason::UnitMismatch {
+                side: Side::Buy,
+                factor: 100
+            }]
+        );
+    }
+
+    #[test]
+    fn value_outside_band_is_suspicious() {
+        let validation = check(430.0, 400.0);
+
+        assert_eq!(validation.verdict(), &Verdict::Suspicious);
+        assert_eq!(
+            validation.reasons(),
+            [Reason::OutOfBand {
+                side: Side::Buy,
+                reference: 400.0,
+                deviation: 0.075
+            }]
+        );
+    }
+
+    #[test]
+    fn missing_value_or_reference_is_not_checked() {
+        let mut validation = Validation::default();
+        let validator = Validator::default();
+        validator.check_side(&mut validation, None, Some(387.0), Side::Sell);
+        validator.check_side(&mut validation, Some(1000.0), None, Side::Sell);
+
+        assert_eq!(validation.verdict(), &Verdict::Valid);
+    }
+
+    #[test]
+    fn buy_above_sell_is_rejected() {
+        let mut banks = [bank("ardshinbank", usd_quote(391.0, 389.0))];
+        validate_banks(&mut banks);
+
+        assert_eq!(usd(&banks[0]).validation().verdict(), &Verdict::Rejected);
+        assert_eq!(reasons(&banks[0]), [Reason::BuyAboveSell]);
+    }
+
+    #[test]
+    fn quotes_are_compared_with_cross_bank_median() {
+        let mut banks = [
+            bank("ardshinbank", usd_quote(385.0, 390.0)),
+            bank("evocabank", usd_quote(386.0, 389.0)),
+            bank("inecobank", usd_quote(384.0, 391.0)),
+            bank("idbank", usd_quote(420.0, 430.0)),
+        ];
+        validate_banks(&mut banks);
+
+        for bank in &banks[..3] {
+            assert_eq!(usd(bank).validation().verdict(), &Verdict::Valid);
+        }
+        // Medians are 385.5 and 390.5
+        assert_eq!(
+            reasons(&banks[3]),
+            [
+                Reason::OutOfBand {
+                    side: Side::Buy,
+                    reference: 385.5,
+                    deviation: (420.0 - 385.5) / 385.5
+                },
+                Reason::OutOfBand {
+                    side: Side::Sell,
+                    reference: 390.5,
+                    deviation: (430.0 - 390.5) / 390.5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_reference_replaces_median() {
+        let mut banks = [
+            bank("ardshinbank", usd_quote(385.0, 390.0)),
+            bank("evocabank", usd_quote(386.0, 389.0)),
+        ];
+        Validator::new(0.01)
+            .with_reference(CurrencyName::USD, CurrencyType::Cash, 400.0)
+            .validate(&mut banks);
+
+        for bank in &banks {
+            assert_eq!(usd(bank).validation().verdict(), &Verdict::Suspicious);
+            assert_eq!(reasons(bank).len(), 2);
+        }
+    }
+
+    #[test]
+    fn median_of_even_count_is_the_mean_of_the_middle_values() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(vec![4.0, 1.0, 3.0, 2.0]), Some(2.5));
+    }
+
+    #[test]
+    fn tiers_are_validated_against_the_currency_references() {
+        let currency = Currency::new(CurrencyName::USD, Some(385.0), Some(390.0)).with_tiers(vec![
+            RateTier::new(1000.0, Some(386.0), Some(389.0)),
+            RateTier::new(5000.0, Some(391.0), Some(388.0)),
+            RateTier::new(10_000.0, Some(0.0), Some(389.0)),
+            RateTier::new(20_000.0, Some(3870.0), None),
+        ]);
+        let mut banks = [bank("ardshinbank", currency)];
+        Validator::default()
+            .with_reference(CurrencyName::USD, CurrencyType::Cash, 387.0)
+            .validate(&mut banks);
+
+        let tiers: Vec<_> = usd(&banks[0])
+            .tiers()
+            .iter()
+            .map(|tier| tier.validation().reasons().to_vec())
+            .collect();
+        assert_eq!(
+            tiers,
+            vec![
+                vec![],
+                vec![Reason::BuyAboveSell],
+                vec![Reason::NonPositive(Side::Buy)],
+                vec![Reason::UnitMismatch {
+                    side: Side::Buy,
+                    factor: 10
+                }],
+            ]
+        );
+        assert_eq!(usd(&banks[0]).validation().verdict(), &Verdict::Valid);
+    }
+
+    #[test]
+    fn rejected_tiers_are_not_applied() {
+        let currency = Currency::new(CurrencyName::USD, Some(385.0), Some(390.0)).with_tiers(vec![
+            RateTier::new(1000.0, Some(386.0), Some(389.0)),
+            RateTier::new(5000.0, Some(391.0), Some(388.0)),
+        ]);
+        let mut banks = [bank("ardshinbank", currency)];
+        validate_banks(&mut banks);
+
+        let currency = usd(&banks[0]);
+        assert_eq!(
+            currency.tier_for(6000.0).map(RateTier::min_amount),
+            Some(1000.0)
+        );
+        assert_eq!(currency.buy_for(6000.0), &Some(386.0));
+        assert_eq!(currency.sell_for(6000.0), &Some(389.0));
+    }
+}
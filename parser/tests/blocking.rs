@@ -0,0 +1,53 @@
+use armbankrate_parser::{blocking, BankImpl, Error};
+
+#[test]
+fn parse_rejects_unknown_bank() {
+    let result = blocking::parse(&["Ardshinbank", "Nosuchbank"]);
+
+    assert!(matches!(result, Err(Error::BankNotFound(name)) if name == "nosuchbank"));
+}
+
+#[test]
+fn parse_json_rejects_unknown_bank() {
+    let result = blocking::parse_json(&["Nosuchbank"]);
+
+    assert!(matches!(result, Err(Error::BankNotFound(name)) if name == "nosuchbank"));
+}
+
+#[test]
+fn parse_without_banks_returns_nothing() {
+    let banks = blocking::parse::<&str>(&[]).unwrap();
+
+    assert!(banks.is_empty());
+    assert_eq!(blocking::parse_json::<&str>(&[]).unwrap(), "{}");
+}
+
+#[test]
+fn parse_is_usable_from_several_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| blocking::parse::<&str>(&[]).map(|banks| banks.len())))
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap().unwrap(), 0);
+    }
+}
+
+#[test]
+fn parse_finds_banks_case_insensitively() {
+    // Names are resolved before any request, the unknown one fails the call without fetching
+    let result = blocking::parse(&["IDBANK", "Unibank", "NoSuchBank"]);
+    assert!(matches!(result, Err(Error::BankNotFound(name)) if name == "nosuchbank"));
+
+    let result = blocking::parse_json(&["UniBank", "No Such Bank"]);
+    assert!(matches!(result, Err(Error::BankNotFound(name)) if name == "no such bank"));
+}
+
+#[test]
+#[ignore = "sends requests to the banks"]
+fn parse_keeps_requested_banks() {
+    let banks = blocking::parse(&["idbank", "UNIBANK"]).unwrap();
+    let names: Vec<&str> = banks.iter().map(|bank| bank.get_name()).collect();
+
+    assert_eq!(names, ["Idbank", "Unibank"]);
+}
@@ -0,0 +1,81 @@
+use armbankrate_parser::branches::{best_branch_rates, branches_json};
+use armbankrate_parser::validation::{validate_banks, Side};
+use armbankrate_parser::{Bank, BankImpl, Currency, CurrencyName};
+use std::str::FromStr;
+
+const BANKS: [&str; 6] = [
+    "ardshinbank",
+    "conversebank",
+    "evocabank",
+    "idbank",
+    "inecobank",
+    "unibank",
+];
+
+/// Bank with the given bank-wide cash rate of USD.
+fn bank(name: &str, buy: f64, sell: f64) -> Bank {
+    let mut bank = Bank::from_str(name).unwrap();
+    bank.cash_currencies_mut().fill_from_currency(Currency::new(
+        CurrencyName::USD,
+        Some(buy),
+        Some(sell),
+    ));
+
+    bank
+}
+
+#[test]
+fn no_bank_provides_branch_lists_yet() {
+    for name in BANKS {
+        let mut bank = Bank::from_str(name).unwrap();
+
+        assert!(bank.branches_url().is_none());
+        bank.parse_branch_list(r#"{"items": [{"name": "Main", "address": "1 Abovyan St"}]}"#)
+            .unwrap();
+        assert!(bank.branches().is_empty());
+    }
+}
+
+#[test]
+fn banks_without_branches_are_left_out_of_json() {
+    let banks = vec![bank("ardshinbank", 385.0, 392.0)];
+
+    assert_eq!(branches_json(&banks).unwrap(), "{}");
+}
+
+#[test]
+fn best_branch_rates_rank_bank_wide_rates() {
+    let banks = vec![
+        bank("ardshinbank", 385.0, 392.0),
+        bank("inecobank", 386.0, 390.0),
+    ];
+
+    let buy = best_branch_rates(&banks, &CurrencyName::USD, Side::Buy, 10);
+    let ranked: Vec<(&str, Option<&str>, f64)> = buy
+        .iter()
+        .map(|rate| (rate.bank(), rate.branch(), rate.rate()))
+        .collect();
+    assert_eq!(
+        ranked,
+        [("Inecobank", None, 386.0), ("Ardshinbank", None, 385.0)]
+    );
+
+    let sell = best_branch_rates(&banks, &CurrencyName::USD, Side::Sell, 1);
+    assert_eq!(sell.len(), 1);
+    assert_eq!((sell[0].bank(), sell[0].rate()), ("Inecobank", 390.0));
+}
+
+#[test]
+fn rejected_rates_are_not_ranked() {
+    let mut banks = vec![
+        bank("ardshinbank", 392.0, 388.0),
+        bank("inecobank", 386.0, 390.0),
+    ];
+    validate_banks(&mut banks);
+
+    for side in [Side::Buy, Side::Sell] {
+        let rates = best_branch_rates(&banks, &CurrencyName::USD, side, 10);
+        let names: Vec<&str> = rates.iter().map(|rate| rate.bank()).collect();
+        assert_eq!(names, ["Inecobank"]);
+    }
+}